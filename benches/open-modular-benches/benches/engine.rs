@@ -1,4 +1,7 @@
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    marker::PhantomData,
+};
 
 use criterion::{
     Criterion,
@@ -6,15 +9,30 @@ use criterion::{
     criterion_group,
     criterion_main,
 };
+use fancy_constructor::new;
 use open_modular_core::Vector;
 use open_modular_engine::{
     bus::Bus,
     module::{
+        ModuleDefine,
+        ModuleDefinition,
+        ModuleDefinitionBuilder,
         ModuleIdentify as _,
+        ModuleInstantiate,
         ModuleSource as _,
+        Version,
+        module,
         module_enum,
     },
-    processor::Processor,
+    port::{
+        PortInputs,
+        PortOutputs,
+    },
+    processor::{
+        Process,
+        ProcessArgs,
+        Processor,
+    },
 };
 use open_modular_modules_generators::Sine;
 use open_modular_modules_utilities::Multiple;
@@ -26,13 +44,75 @@ use uuid::Uuid;
 
 criterion_main!(engine);
 
-criterion_group!(engine, process_sin_750);
+criterion_group!(
+    engine,
+    process_sin_750,
+    process_sin_750_unreachable,
+    process_mult_chain_750,
+    process_mult_chain_750_threads_4
+);
 
 // -------------------------------------------------------------------------------------------------
 
 // Processing
 
 fn process_sin_750(criterion: &mut Criterion) {
+    let mut processor = processor(true);
+    let context = ();
+    let mut output = vec![Vector::default(); 2];
+
+    criterion.bench_function("process sin 750", |bencher| {
+        bencher.iter(|| {
+            for i in 0..750 {
+                processor.process(&context, black_box(i), &mut output);
+            }
+        });
+    });
+}
+
+fn process_sin_750_unreachable(criterion: &mut Criterion) {
+    let mut processor = processor(false);
+    let context = ();
+    let mut output = vec![Vector::default(); 2];
+
+    criterion.bench_function("process sin 750 unreachable", |bencher| {
+        bencher.iter(|| {
+            for i in 0..750 {
+                processor.process(&context, black_box(i), &mut output);
+            }
+        });
+    });
+}
+
+fn process_mult_chain_750(criterion: &mut Criterion) {
+    let mut processor = processor_chain(1);
+    let context = ();
+    let mut output = vec![Vector::default(); 2];
+
+    criterion.bench_function("process mult chain 750", |bencher| {
+        bencher.iter(|| {
+            for i in 0..750 {
+                processor.process(&context, black_box(i), &mut output);
+            }
+        });
+    });
+}
+
+fn process_mult_chain_750_threads_4(criterion: &mut Criterion) {
+    let mut processor = processor_chain(4);
+    let context = ();
+    let mut output = vec![Vector::default(); 2];
+
+    criterion.bench_function("process mult chain 750 threads 4", |bencher| {
+        bencher.iter(|| {
+            for i in 0..750 {
+                processor.process(&context, black_box(i), &mut output);
+            }
+        });
+    });
+}
+
+fn processor(sink: bool) -> Processor<Module<()>> {
     let bus = Bus::default();
     let receiver = bus.split().1;
 
@@ -42,24 +122,80 @@ fn process_sin_750(criterion: &mut Criterion) {
         let a_id = Uuid::new_v4();
         let b_id = Uuid::new_v4();
 
-        processor.add(a_id, Module::get(&Sine::<()>::id(), ()));
-        processor.add(b_id, Module::get(&Multiple::<()>::id(), ()));
+        processor.add(
+            a_id,
+            Module::get(&Sine::<()>::id(), (), None).expect("module to exist"),
+        );
+        processor.add(
+            b_id,
+            Module::get(&Multiple::<()>::id(), (), None).expect("module to exist"),
+        );
 
         unsafe {
-            processor.connect(a_id, 0, b_id, 0);
+            processor.connect(b_id, 0, a_id, 0);
+        }
+
+        if sink {
+            let c_id = Uuid::new_v4();
+
+            processor.add(
+                c_id,
+                Module::get(&Sink::<()>::id(), (), None).expect("module to exist"),
+            );
+
+            unsafe {
+                processor.connect(c_id, 0, b_id, 0);
+            }
         }
     }
 
-    let context = ();
-    let mut output = vec![Vector::default(); 2];
+    processor
+}
 
-    criterion.bench_function("process sin 750", |bencher| {
-        bencher.iter(|| {
-            for i in 0..750 {
-                processor.process(&context, black_box(i), &mut output);
+// A single oscillator feeding a chain of 250 multiples, where each multiple
+// feeds the next in the chain and three sinks (approximately 1000 connected
+// output ports).
+fn processor_chain(threads: usize) -> Processor<Module<()>> {
+    let bus = Bus::default();
+    let receiver = bus.split().1;
+
+    let mut processor = Processor::<Module<()>>::with_threads(receiver, threads);
+    let mut previous_id = Uuid::new_v4();
+
+    processor.add(
+        previous_id,
+        Module::get(&Sine::<()>::id(), (), None).expect("module to exist"),
+    );
+
+    for _ in 0..250 {
+        let multiple_id = Uuid::new_v4();
+
+        processor.add(
+            multiple_id,
+            Module::get(&Multiple::<()>::id(), (), None).expect("module to exist"),
+        );
+
+        unsafe {
+            processor.connect(multiple_id, 0, previous_id, 0);
+        }
+
+        for output_port in 1..4 {
+            let sink_id = Uuid::new_v4();
+
+            processor.add(
+                sink_id,
+                Module::get(&Sink::<()>::id(), (), None).expect("module to exist"),
+            );
+
+            unsafe {
+                processor.connect(sink_id, 0, multiple_id, output_port);
             }
-        });
-    });
+        }
+
+        previous_id = multiple_id;
+    }
+
+    processor
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -74,4 +210,44 @@ where
 {
     Sine,
     Multiple,
+    Sink,
+}
+
+// Sink
+
+#[module(id = "c0b4e1d9-3a0f-4f8e-9d55-0b8f3c2a6e71")]
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct Sink<R> {
+    port_inputs: PortInputs,
+    port_outputs: PortOutputs,
+    #[new(default)]
+    _r: PhantomData<R>,
+}
+
+impl<R> ModuleDefine for Sink<R> {
+    fn define(module: ModuleDefinitionBuilder) -> impl Into<ModuleDefinition> {
+        module
+            .name("bench/sink")
+            .version(Version::new(1, 0, 0))
+            .sink(true)
+            .with_input(|input| input.name("Input"))
+    }
+}
+
+impl<R> ModuleInstantiate for Sink<R> {
+    type Context = R;
+
+    fn instantiate(
+        _context: Self::Context,
+        _config: Self::Config,
+        port_inputs: PortInputs,
+        port_outputs: PortOutputs,
+    ) -> Self {
+        Self::new(port_inputs, port_outputs)
+    }
+}
+
+impl<R> Process for Sink<R> {
+    fn process(&mut self, _args: &ProcessArgs) {}
 }
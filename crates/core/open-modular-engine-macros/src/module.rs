@@ -24,6 +24,12 @@ use uuid::Uuid;
 #[derive(Debug, FromMeta)]
 pub(crate) struct Args {
     id: String,
+    #[darling(default)]
+    config: bool,
+    #[darling(default)]
+    migrate: bool,
+    #[darling(default)]
+    state: bool,
 }
 
 impl Args {
@@ -61,6 +67,77 @@ pub(crate) fn module_tokens(args: &Args, item: &ItemStruct) -> TokenStream2 {
     let uuid = Uuid::from_str(&args.id).expect("valid id");
     let uuid_value = uuid.as_bytes();
 
+    // Migrate (a default implementation is generated unless the module provides
+    // an implementation)
+
+    let migrate = (!args.migrate).then(|| {
+        quote::quote! {
+            impl #generics ::open_modular_engine::module::ModuleMigrate for #ident #generics #where_clause {}
+        }
+    });
+
+    // Definition (configured by the config of the instance where the module is
+    // configurable)
+
+    let definition = if args.config {
+        quote::quote! {
+            <Self as ::open_modular_engine::module::ModuleInstantiate>::configure(
+                <Self as ::open_modular_engine::module::ModuleDefine>::define(
+                    ::open_modular_engine::module::ModuleDefinition::builder()
+                ).into(),
+                &self.config,
+            )
+        }
+    } else {
+        quote::quote! {
+            <Self as ::open_modular_engine::module::ModuleDefine>::define(
+                ::open_modular_engine::module::ModuleDefinition::builder()
+            ).into()
+        }
+    };
+
+    // Snapshot (delegating to the module implementation of state where the
+    // module has state)
+
+    let snapshot = if args.state {
+        quote::quote! {
+            impl #generics ::open_modular_engine::module::ModuleSnapshot for #ident #generics #where_clause {
+                fn snapshot(&self) -> Option<::open_modular_engine::_dependencies::serde_json::Value> {
+                    let state = <Self as ::open_modular_engine::module::ModuleState>::state(self);
+                    let snapshot = ::open_modular_engine::_dependencies::serde_json::to_value(state)
+                        .expect("module state to be serialized");
+
+                    Some(snapshot)
+                }
+
+                fn restore_snapshot(&mut self, snapshot: ::open_modular_engine::_dependencies::serde_json::Value) {
+                    let state = ::open_modular_engine::_dependencies::serde_json::from_value(snapshot)
+                        .expect("module state to be deserialized");
+
+                    <Self as ::open_modular_engine::module::ModuleState>::restore(self, state);
+                }
+
+                fn validate_snapshot(
+                    snapshot: &::open_modular_engine::_dependencies::serde_json::Value,
+                ) -> ::open_modular_engine::_dependencies::serde_json::Result<()> {
+                    ::open_modular_engine::_dependencies::serde_json::from_value::<
+                        <Self as ::open_modular_engine::module::ModuleState>::State
+                    >(snapshot.clone()).map(drop)
+                }
+            }
+        }
+    } else {
+        quote::quote! {
+            impl #generics ::open_modular_engine::module::ModuleSnapshot for #ident #generics #where_clause {
+                fn snapshot(&self) -> Option<::open_modular_engine::_dependencies::serde_json::Value> {
+                    None
+                }
+
+                fn restore_snapshot(&mut self, _snapshot: ::open_modular_engine::_dependencies::serde_json::Value) {}
+            }
+        }
+    };
+
     // Tokens
 
     quote::quote! {
@@ -93,9 +170,21 @@ pub(crate) fn module_tokens(args: &Args, item: &ItemStruct) -> TokenStream2 {
                 }
             }
 
+            // Migrate
+
+            #migrate
+
             // Module
 
-            impl #generics ::open_modular_engine::module::Module for #ident #generics #where_clause {}
+            impl #generics ::open_modular_engine::module::Module for #ident #generics #where_clause {
+                fn definition(&self) -> ::open_modular_engine::module::ModuleDefinition {
+                    #definition
+                }
+            }
+
+            // Snapshot
+
+            #snapshot
 
             // Constant Functions
 
@@ -111,6 +111,26 @@ pub(crate) fn module_enum_tokens(args: &Args, item: &ItemEnum) -> TokenStream2 {
                 }
             }
 
+            // Catalog
+
+            impl #generics ::open_modular_engine::catalog::ModuleCatalog for #ident #generics #where_clause {
+                fn catalog() -> ::open_modular_engine::catalog::Catalog {
+                    ::open_modular_engine::catalog::Catalog::new(vec![
+                    #(
+                        ::open_modular_engine::catalog::CatalogModule::new(
+                            <#variant::#generics as ::open_modular_engine::module::ModuleIdentify>::id(),
+                            <#variant::#generics as ::open_modular_engine::module::ModuleInstantiate>::configure(
+                                <#variant::#generics as ::open_modular_engine::module::ModuleDefine>::define(
+                                    ::open_modular_engine::module::ModuleDefinition::builder()
+                                ).into(),
+                                &Default::default(),
+                            ),
+                        )
+                    ),*
+                    ])
+                }
+            }
+
             // From<ModuleA<'rt>>, From<ModuleB<'rt>>, etc.
 
         #(
@@ -129,9 +149,74 @@ pub(crate) fn module_enum_tokens(args: &Args, item: &ItemEnum) -> TokenStream2 {
                 }
             }
 
+            // Migrate Source
+
+            impl #generics ::open_modular_engine::module::ModuleMigrateSource for #ident #generics #where_clause {
+                fn migrates(
+                    id: &::open_modular_engine::_dependencies::uuid::Uuid,
+                    version: &::open_modular_engine::module::Version,
+                ) -> bool {
+                #(
+                    if id == &<#variant::#generics as ::open_modular_engine::module::ModuleIdentify>::id() {
+                        return <#variant::#generics as ::open_modular_engine::module::ModuleMigrate>::migrates(version);
+                    }
+                )*
+
+                    false
+                }
+
+                fn migrate_input(
+                    id: &::open_modular_engine::_dependencies::uuid::Uuid,
+                    version: &::open_modular_engine::module::Version,
+                    port: usize,
+                ) -> Option<usize> {
+                #(
+                    if id == &<#variant::#generics as ::open_modular_engine::module::ModuleIdentify>::id() {
+                        return <#variant::#generics as ::open_modular_engine::module::ModuleMigrate>::migrate_input(version, port);
+                    }
+                )*
+
+                    Some(port)
+                }
+
+                fn migrate_output(
+                    id: &::open_modular_engine::_dependencies::uuid::Uuid,
+                    version: &::open_modular_engine::module::Version,
+                    port: usize,
+                ) -> Option<usize> {
+                #(
+                    if id == &<#variant::#generics as ::open_modular_engine::module::ModuleIdentify>::id() {
+                        return <#variant::#generics as ::open_modular_engine::module::ModuleMigrate>::migrate_output(version, port);
+                    }
+                )*
+
+                    Some(port)
+                }
+
+                fn migrate_state(
+                    id: &::open_modular_engine::_dependencies::uuid::Uuid,
+                    version: &::open_modular_engine::module::Version,
+                    state: Option<::open_modular_engine::_dependencies::serde_json::Value>,
+                ) -> Option<::open_modular_engine::_dependencies::serde_json::Value> {
+                #(
+                    if id == &<#variant::#generics as ::open_modular_engine::module::ModuleIdentify>::id() {
+                        return <#variant::#generics as ::open_modular_engine::module::ModuleMigrate>::migrate_state(version, state);
+                    }
+                )*
+
+                    state
+                }
+            }
+
             // Module
 
-            impl #generics ::open_modular_engine::module::Module for #ident #generics #where_clause {}
+            impl #generics ::open_modular_engine::module::Module for #ident #generics #where_clause {
+                fn definition(&self) -> ::open_modular_engine::module::ModuleDefinition {
+                    match self {
+                        #(Self::#variant(module) => ::open_modular_engine::module::Module::definition(module)),*
+                    }
+                }
+            }
 
             // Module Source
 
@@ -140,17 +225,93 @@ pub(crate) fn module_enum_tokens(args: &Args, item: &ItemEnum) -> TokenStream2 {
 
                 fn get(
                     id: &::open_modular_engine::_dependencies::uuid::Uuid,
-                    context: Self::Context
-                ) -> Self {
-                    let definitions = Self::definitions();
-                    let definition = definitions.get(id).unwrap();
+                    context: Self::Context,
+                    config: Option<::open_modular_engine::module::ModuleConfig>,
+                ) -> Option<Self> {
                     let instantiations = Self::instantiations();
-                    let instantiation = instantiations.get(id).unwrap();
+                    let instantiation = instantiations.get(id)?;
+
+                    instantiation(context, config)
+                }
+            }
+
+            // Module Configure Source
+
+            impl #generics ::open_modular_engine::module::ModuleConfigureSource for #ident #generics #where_clause {
+                fn config(
+                    id: &::open_modular_engine::_dependencies::uuid::Uuid,
+                    config: ::open_modular_engine::_dependencies::serde_json::Value,
+                ) -> Option<
+                    ::open_modular_engine::_dependencies::serde_json::Result<
+                        ::open_modular_engine::module::ModuleConfig
+                    >
+                > {
+                #(
+                    if id == &<#variant::#generics as ::open_modular_engine::module::ModuleIdentify>::id() {
+                        return Some(
+                            ::open_modular_engine::_dependencies::serde_json::from_value::<
+                                <#variant::#generics as ::open_modular_engine::module::ModuleInstantiate>::Config
+                            >(config).map(::open_modular_engine::module::ModuleConfig::new)
+                        );
+                    }
+                )*
+
+                    None
+                }
+
+                fn configure(
+                    id: &::open_modular_engine::_dependencies::uuid::Uuid,
+                    config: &::open_modular_engine::module::ModuleConfig,
+                ) -> Option<::open_modular_engine::module::ModuleDefinition> {
+                #(
+                    if id == &<#variant::#generics as ::open_modular_engine::module::ModuleIdentify>::id() {
+                        return config.get().map(|config| {
+                            <#variant::#generics as ::open_modular_engine::module::ModuleInstantiate>::configure(
+                                <#variant::#generics as ::open_modular_engine::module::ModuleDefine>::define(
+                                    ::open_modular_engine::module::ModuleDefinition::builder()
+                                ).into(),
+                                config,
+                            )
+                        });
+                    }
+                )*
+
+                    None
+                }
+            }
+
+            // Snapshot
+
+            impl #generics ::open_modular_engine::module::ModuleSnapshot for #ident #generics #where_clause {
+                fn snapshot(&self) -> Option<::open_modular_engine::_dependencies::serde_json::Value> {
+                    match self {
+                        #(Self::#variant(module) => ::open_modular_engine::module::ModuleSnapshot::snapshot(module)),*
+                    }
+                }
+
+                fn restore_snapshot(&mut self, snapshot: ::open_modular_engine::_dependencies::serde_json::Value) {
+                    match self {
+                        #(Self::#variant(module) => ::open_modular_engine::module::ModuleSnapshot::restore_snapshot(module, snapshot)),*
+                    }
+                }
+            }
 
-                    let port_inputs = ::open_modular_engine::port::PortInputs::from_definition(definition);
-                    let port_outputs = ::open_modular_engine::port::PortOutputs::from_definition(definition);
+            // Snapshot Source
 
-                    instantiation(context, port_inputs, port_outputs)
+            impl #generics ::open_modular_engine::module::ModuleSnapshotSource for #ident #generics #where_clause {
+                fn validate(
+                    id: &::open_modular_engine::_dependencies::uuid::Uuid,
+                    snapshot: &::open_modular_engine::_dependencies::serde_json::Value,
+                ) -> Option<::open_modular_engine::_dependencies::serde_json::Result<()>> {
+                #(
+                    if id == &<#variant::#generics as ::open_modular_engine::module::ModuleIdentify>::id() {
+                        return Some(
+                            <#variant::#generics as ::open_modular_engine::module::ModuleSnapshot>::validate_snapshot(snapshot)
+                        );
+                    }
+                )*
+
+                    None
                 }
             }
 
@@ -159,39 +320,38 @@ pub(crate) fn module_enum_tokens(args: &Args, item: &ItemEnum) -> TokenStream2 {
             impl #generics ::open_modular_engine::processor::Process for #ident #generics #where_clause {
                 fn process(&mut self, args: &::open_modular_engine::processor::ProcessArgs) {
                     match self {
-                        #(Self::#variant(module) => module.process(args)),*
+                        #(Self::#variant(module) => ::open_modular_engine::processor::Process::process(module, args)),*
                     }
                 }
-            }
 
-            // Constant Functions
+                fn reset(&mut self) {
+                    match self {
+                        #(Self::#variant(module) => ::open_modular_engine::processor::Process::reset(module)),*
+                    }
+                }
 
-            impl #generics #ident #generics #where_clause {
-                #[doc(hidden)]
-                const fn definitions() ->
-                    ::std::sync::LazyLock<
-                        ::std::collections::HashMap<
-                            ::open_modular_engine::_dependencies::uuid::Uuid,
-                            ::open_modular_engine::module::ModuleDefinition
-                        >
-                    >
-                {
-                    ::std::sync::LazyLock::new(|| {
-                        let mut definitions = ::std::collections::HashMap::new();
+                fn on_connect(&mut self, port: ::open_modular_engine::port::PortIndex) {
+                    match self {
+                        #(Self::#variant(module) => ::open_modular_engine::processor::Process::on_connect(module, port)),*
+                    }
+                }
 
-                    #(
-                        definitions.insert(
-                            <#variant::#generics as ::open_modular_engine::module::ModuleIdentify>::id(),
-                            <#variant::#generics as ::open_modular_engine::module::ModuleDefine>::define(
-                                ::open_modular_engine::module::ModuleDefinition::builder()
-                            ).into(),
-                        );
-                    )*
+                fn on_disconnect(&mut self, port: ::open_modular_engine::port::PortIndex) {
+                    match self {
+                        #(Self::#variant(module) => ::open_modular_engine::processor::Process::on_disconnect(module, port)),*
+                    }
+                }
 
-                        definitions
-                    })
+                fn on_remove(&mut self) {
+                    match self {
+                        #(Self::#variant(module) => ::open_modular_engine::processor::Process::on_remove(module)),*
+                    }
                 }
+            }
 
+            // Constant Functions
+
+            impl #generics #ident #generics #where_clause {
                 #[doc(hidden)]
                 const fn instantiations() ->
                     ::std::sync::LazyLock<
@@ -199,9 +359,8 @@ pub(crate) fn module_enum_tokens(args: &Args, item: &ItemEnum) -> TokenStream2 {
                             ::open_modular_engine::_dependencies::uuid::Uuid,
                             Box<dyn Fn(
                                 #type_param,
-                                ::open_modular_engine::port::PortInputs,
-                                ::open_modular_engine::port::PortOutputs,
-                            ) -> #ident #generics>
+                                Option<::open_modular_engine::module::ModuleConfig>,
+                            ) -> Option<#ident #generics>>
                         >
                     >
                 {
@@ -211,20 +370,36 @@ pub(crate) fn module_enum_tokens(args: &Args, item: &ItemEnum) -> TokenStream2 {
                     #(
                         instantiations.insert(
                             <#variant::#generics as ::open_modular_engine::module::ModuleIdentify>::id(),
-                            Box::new(|context, port_inputs, port_outputs| {
-                                #ident::#variant(
+                            Box::new(|context, config: Option<::open_modular_engine::module::ModuleConfig>| {
+                                let config: <#variant::#generics as ::open_modular_engine::module::ModuleInstantiate>::Config =
+                                    match config {
+                                        Some(config) => config.into_inner()?,
+                                        None => Default::default(),
+                                    };
+
+                                let definition = <#variant::#generics as ::open_modular_engine::module::ModuleInstantiate>::configure(
+                                    <#variant::#generics as ::open_modular_engine::module::ModuleDefine>::define(
+                                        ::open_modular_engine::module::ModuleDefinition::builder()
+                                    ).into(),
+                                    &config,
+                                );
+
+                                let port_inputs = ::open_modular_engine::port::PortInputs::from_definition(&definition);
+                                let port_outputs = ::open_modular_engine::port::PortOutputs::from_definition(&definition);
+
+                                Some(#ident::#variant(
                                     <#variant::#generics as ::open_modular_engine::module::ModuleInstantiate>::instantiate(
                                         context,
+                                        config,
                                         port_inputs,
                                         port_outputs,
                                     )
-                                )
+                                ))
                             })
                             as Box<dyn Fn(
                                 #type_param,
-                                ::open_modular_engine::port::PortInputs,
-                                ::open_modular_engine::port::PortOutputs,
-                            ) -> #ident #generics>
+                                Option<::open_modular_engine::module::ModuleConfig>,
+                            ) -> Option<#ident #generics>>
                         );
                     )*
 
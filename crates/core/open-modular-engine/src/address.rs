@@ -0,0 +1,555 @@
+//! # Address
+//!
+//! The `address` module provides name-based addressing for scripts, patch
+//! files, and other tooling. Rather than module ids, instance ids, and raw port
+//! indices, modules are referenced by definition name (such as
+//! "oscillator/sine"), instances by a user-assigned label, and ports by either
+//! the name given in the port definition or the port index.
+//!
+//! Addressed protocol messages are resolved to engine protocol messages by an
+//! `AddressResolver`, which holds the catalog of available modules and the
+//! current set of labelled instances (with the definition of each instance,
+//! as configured - the ports of a configurable module depend on the
+//! configuration of the instance). As with patch loading, resolution (and
+//! all associated validation) is intended to happen outside of the engine, so
+//! that the engine only ever receives instructions which are expected to
+//! succeed - the resolver therefore also tracks the connections it has
+//! resolved (or registered), refusing to connect a port which is already
+//! connected, or to disconnect a port which is not.
+
+use std::{
+    collections::{
+        HashMap,
+        HashSet,
+    },
+    fmt::{
+        self,
+        Debug,
+        Display,
+        Formatter,
+    },
+    marker::PhantomData,
+};
+
+use fancy_constructor::new;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::Value;
+use snafu::{
+    OptionExt as _,
+    ResultExt as _,
+    Snafu,
+    ensure,
+};
+use uuid::Uuid;
+
+use crate::{
+    catalog::Catalog,
+    module::{
+        ModuleConfig,
+        ModuleConfigureSource,
+        ModuleDefinition,
+    },
+    patch::Patch,
+    port::PortIndex,
+    protocol::{
+        Protocol,
+        ProtocolAdd,
+        ProtocolConnect,
+        ProtocolDisconnect,
+        ProtocolRemove,
+    },
+};
+
+// =================================================================================================
+// Address
+// =================================================================================================
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum AddressProtocol {
+    Add(AddressAdd),
+    Connect(AddressConnect),
+    Disconnect(AddressDisconnect),
+    Remove(AddressRemove),
+}
+
+impl From<AddressAdd> for AddressProtocol {
+    fn from(add: AddressAdd) -> Self {
+        Self::Add(add)
+    }
+}
+
+impl From<AddressConnect> for AddressProtocol {
+    fn from(connect: AddressConnect) -> Self {
+        Self::Connect(connect)
+    }
+}
+
+impl From<AddressDisconnect> for AddressProtocol {
+    fn from(disconnect: AddressDisconnect) -> Self {
+        Self::Disconnect(disconnect)
+    }
+}
+
+impl From<AddressRemove> for AddressProtocol {
+    fn from(remove: AddressRemove) -> Self {
+        Self::Remove(remove)
+    }
+}
+
+// Add
+
+/// Adds an instance of the module with the given (definition) name, assigning
+/// the given label to the new instance, optionally with a (serialized)
+/// instantiation-time configuration.
+#[derive(new, Clone, Debug, Deserialize, Serialize)]
+pub struct AddressAdd {
+    #[new(into)]
+    pub label: String,
+    #[new(into)]
+    pub module: String,
+    #[new(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<Value>,
+}
+
+impl AddressAdd {
+    /// Sets the (serialized) instantiation-time configuration of the new
+    /// instance, which otherwise has the default configuration of the module.
+    #[must_use]
+    pub fn with_config(mut self, config: Value) -> Self {
+        self.config = Some(config);
+        self
+    }
+}
+
+// Connect
+
+/// Connects an output port of a labelled instance to an input port of a
+/// labelled instance.
+#[derive(new, Clone, Debug, Deserialize, Serialize)]
+pub struct AddressConnect {
+    #[new(into)]
+    pub input: String,
+    #[new(into)]
+    pub input_port: AddressPort,
+    #[new(into)]
+    pub output: String,
+    #[new(into)]
+    pub output_port: AddressPort,
+}
+
+// Disconnect
+
+/// Disconnects an input port of a labelled instance.
+#[derive(new, Clone, Debug, Deserialize, Serialize)]
+pub struct AddressDisconnect {
+    #[new(into)]
+    pub input: String,
+    #[new(into)]
+    pub input_port: AddressPort,
+}
+
+// Remove
+
+/// Removes a labelled instance (the label may then be reused).
+#[derive(new, Clone, Debug, Deserialize, Serialize)]
+pub struct AddressRemove {
+    #[new(into)]
+    pub label: String,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Port
+
+/// An `AddressPort` identifies a port of an instance either by the name given
+/// in the port definition, or by index. When serialized, a port is either a
+/// string (a name) or a number (an index).
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum AddressPort {
+    Index(usize),
+    Name(String),
+}
+
+impl AddressPort {
+    /// Gets the index of the port within the given module definition, if the
+    /// port exists.
+    #[must_use]
+    pub fn index(&self, definition: &ModuleDefinition, port: PortIndex) -> Option<usize> {
+        let names = match port {
+            PortIndex::Input(_) => definition
+                .inputs
+                .iter()
+                .map(|input| input.name.as_deref())
+                .collect::<Vec<_>>(),
+            PortIndex::Output(_) => definition
+                .outputs
+                .iter()
+                .map(|output| output.name.as_deref())
+                .collect::<Vec<_>>(),
+        };
+
+        match self {
+            Self::Index(index) => (*index < names.len()).then_some(*index),
+            Self::Name(name) => names.iter().position(|port| *port == Some(name.as_str())),
+        }
+    }
+}
+
+impl Display for AddressPort {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Index(index) => write!(f, "{index}"),
+            Self::Name(name) => write!(f, "\"{name}\""),
+        }
+    }
+}
+
+impl From<usize> for AddressPort {
+    fn from(index: usize) -> Self {
+        Self::Index(index)
+    }
+}
+
+impl From<&str> for AddressPort {
+    fn from(name: &str) -> Self {
+        Self::Name(name.to_owned())
+    }
+}
+
+impl From<String> for AddressPort {
+    fn from(name: String) -> Self {
+        Self::Name(name)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Resolver
+
+/// An `AddressResolver` resolves addressed protocol messages to engine
+/// protocol messages, tracking the labelled instances which have been added
+/// (or registered) and not yet removed, and the connections between them (as
+/// a map of input ports to the output ports to which they are connected). The
+/// resolver is generic over the module enumeration from which the catalog was
+/// produced, which is used to configure the definitions of instances.
+#[derive(new, Debug)]
+pub struct AddressResolver<M>
+where
+    M: Debug,
+{
+    catalog: Catalog,
+    #[new(default)]
+    connections: HashMap<(Uuid, usize), (Uuid, usize)>,
+    #[new(default)]
+    instances: HashMap<String, AddressInstance>,
+    #[new(default)]
+    _m: PhantomData<M>,
+}
+
+impl<M> AddressResolver<M>
+where
+    M: Debug + ModuleConfigureSource,
+{
+    /// Gets the id of the instance with the given label, if present.
+    #[must_use]
+    pub fn instance(&self, label: &str) -> Option<Uuid> {
+        self.instances.get(label).map(|instance| instance.instance)
+    }
+
+    /// Registers an existing instance (such as an instance added by loading a
+    /// patch) under the given label, with the (serialized) configuration of the
+    /// instance, if the instance does not have the default configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the label is already in use, if the instance is
+    /// already registered (under another label), if the module is not found,
+    /// or if the configuration is invalid.
+    pub fn register(
+        &mut self,
+        label: impl Into<String>,
+        instance: Uuid,
+        module: Uuid,
+        config: Option<Value>,
+    ) -> Result<(), AddressError> {
+        let label = label.into();
+
+        ensure!(!self.instances.contains_key(&label), LabelExistsError {
+            label
+        });
+
+        ensure!(
+            !self
+                .instances
+                .values()
+                .any(|existing| existing.instance == instance),
+            InstanceExistsError { instance }
+        );
+
+        let (definition, _) = self.configure(&label, module, config)?;
+
+        self.instances
+            .insert(label, AddressInstance::new(definition, instance));
+
+        Ok(())
+    }
+
+    /// Registers every labelled instance of a patch (instances of sub-patches
+    /// are not registered, as they are not single instances once loaded), and
+    /// every connection of the patch between instances of modules (so that the
+    /// connected ports are known to be connected). The patch is expected to be
+    /// current (migrated, where necessary, as when loaded).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any label is already in use, if any instance is
+    /// already registered, or if the configuration of any instance is invalid.
+    pub fn register_patch(&mut self, patch: &Patch) -> Result<(), AddressError> {
+        for instance in &patch.instances {
+            if let Some(label) = &instance.label
+                && self.catalog.get(&instance.module).is_some()
+            {
+                self.register(
+                    label.clone(),
+                    instance.instance,
+                    instance.module,
+                    instance.config.clone(),
+                )?;
+            }
+        }
+
+        let modules = patch
+            .instances
+            .iter()
+            .filter(|instance| self.catalog.get(&instance.module).is_some())
+            .map(|instance| instance.instance)
+            .collect::<HashSet<_>>();
+
+        for connection in &patch.connections {
+            if modules.contains(&connection.input_instance)
+                && modules.contains(&connection.output_instance)
+            {
+                self.connections.insert(
+                    (connection.input_instance, connection.input_port),
+                    (connection.output_instance, connection.output_port),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Gets the definition of the module with the given id, as configured by
+    /// the given configuration (or the catalogued definition where none is
+    /// given), along with the deserialized configuration.
+    fn configure(
+        &self,
+        label: &str,
+        module: Uuid,
+        config: Option<Value>,
+    ) -> Result<(ModuleDefinition, Option<ModuleConfig>), AddressError> {
+        match config {
+            Some(config) => {
+                let config = M::config(&module, config)
+                    .context(ModuleIdNotFoundError { module })?
+                    .context(ConfigError { label })?;
+
+                let definition =
+                    M::configure(&module, &config).context(ModuleIdNotFoundError { module })?;
+
+                Ok((definition, Some(config)))
+            }
+            None => self
+                .catalog
+                .get(&module)
+                .map(|module| (module.definition.clone(), None))
+                .context(ModuleIdNotFoundError { module }),
+        }
+    }
+}
+
+impl<M> AddressResolver<M>
+where
+    M: Debug + ModuleConfigureSource,
+{
+    /// Resolves an addressed protocol message to an engine protocol message.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any module, label, or port cannot be resolved, if a
+    /// new label is already in use, if a port to be connected is already
+    /// connected, or if a port to be disconnected is not connected.
+    pub fn resolve(
+        &mut self,
+        protocol: impl Into<AddressProtocol>,
+    ) -> Result<Protocol, AddressError> {
+        match protocol.into() {
+            AddressProtocol::Add(add) => self.resolve_add(add),
+            AddressProtocol::Connect(connect) => self.resolve_connect(&connect),
+            AddressProtocol::Disconnect(disconnect) => self.resolve_disconnect(&disconnect),
+            AddressProtocol::Remove(remove) => self.resolve_remove(&remove),
+        }
+    }
+
+    fn resolve_add(&mut self, add: AddressAdd) -> Result<Protocol, AddressError> {
+        ensure!(!self.instances.contains_key(&add.label), LabelExistsError {
+            label: add.label
+        });
+
+        let module = self
+            .catalog
+            .get_by_name(&add.module)
+            .context(ModuleNotFoundError { module: add.module })?
+            .id;
+
+        let (definition, config) = self.configure(&add.label, module, add.config)?;
+        let instance = Uuid::new_v4();
+
+        self.instances
+            .insert(add.label, AddressInstance::new(definition, instance));
+
+        let protocol = ProtocolAdd::new(instance, module);
+
+        Ok(match config {
+            Some(config) => protocol.with_config(config),
+            None => protocol,
+        }
+        .into())
+    }
+
+    fn resolve_connect(&mut self, connect: &AddressConnect) -> Result<Protocol, AddressError> {
+        let input = self.port(&connect.input, &connect.input_port, PortIndex::Input(0))?;
+        let output = self.port(&connect.output, &connect.output_port, PortIndex::Output(0))?;
+
+        ensure!(
+            !self.connections.contains_key(&input),
+            InputConnectedError {
+                label: &connect.input,
+                port: connect.input_port.clone(),
+            }
+        );
+
+        ensure!(
+            !self
+                .connections
+                .values()
+                .any(|connected| *connected == output),
+            OutputConnectedError {
+                label: &connect.output,
+                port: connect.output_port.clone(),
+            }
+        );
+
+        self.connections.insert(input, output);
+
+        Ok(ProtocolConnect::new(input.0, input.1, output.0, output.1).into())
+    }
+
+    fn resolve_disconnect(
+        &mut self,
+        disconnect: &AddressDisconnect,
+    ) -> Result<Protocol, AddressError> {
+        let input = self.port(
+            &disconnect.input,
+            &disconnect.input_port,
+            PortIndex::Input(0),
+        )?;
+
+        ensure!(
+            self.connections.remove(&input).is_some(),
+            InputNotConnectedError {
+                label: &disconnect.input,
+                port: disconnect.input_port.clone(),
+            }
+        );
+
+        Ok(ProtocolDisconnect::new(input.0, input.1).into())
+    }
+
+    fn resolve_remove(&mut self, remove: &AddressRemove) -> Result<Protocol, AddressError> {
+        let instance = self
+            .instances
+            .remove(&remove.label)
+            .context(LabelNotFoundError {
+                label: &remove.label,
+            })?;
+
+        // Removing an instance disconnects any connections to or from the
+        // instance (see `Processor::remove`).
+
+        self.connections.retain(|(input, _), (output, _)| {
+            *input != instance.instance && *output != instance.instance
+        });
+
+        Ok(ProtocolRemove::new(instance.instance).into())
+    }
+
+    /// Resolves a port of a labelled instance (of the kind given by `kind`) to
+    /// the instance id and port index.
+    fn port(
+        &self,
+        label: &str,
+        port: &AddressPort,
+        kind: PortIndex,
+    ) -> Result<(Uuid, usize), AddressError> {
+        let instance = self
+            .instances
+            .get(label)
+            .context(LabelNotFoundError { label })?;
+
+        let index = port
+            .index(&instance.definition, kind)
+            .context(PortNotFoundError {
+                label,
+                port: port.clone(),
+            })?;
+
+        Ok((instance.instance, index))
+    }
+}
+
+/// An `AddressInstance` is a labelled instance, with the definition of the
+/// instance as configured (against which ports are resolved).
+#[derive(new, Debug)]
+struct AddressInstance {
+    definition: ModuleDefinition,
+    instance: Uuid,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Error
+
+#[derive(Debug, Snafu)]
+#[snafu(context(suffix(Error)), visibility(pub(crate)))]
+pub enum AddressError {
+    #[snafu(display("config of instance \"{label}\" is invalid"))]
+    Config {
+        label: String,
+        source: serde_json::Error,
+    },
+    #[snafu(display("input port {port} of instance \"{label}\" is already connected"))]
+    InputConnected { label: String, port: AddressPort },
+    #[snafu(display("input port {port} of instance \"{label}\" is not connected"))]
+    InputNotConnected { label: String, port: AddressPort },
+    #[snafu(display("instance {instance} already registered"))]
+    InstanceExists { instance: Uuid },
+    #[snafu(display("label \"{label}\" already in use"))]
+    LabelExists { label: String },
+    #[snafu(display("label \"{label}\" not found"))]
+    LabelNotFound { label: String },
+    #[snafu(display("module {module} not found"))]
+    ModuleIdNotFound { module: Uuid },
+    #[snafu(display("module \"{module}\" not found"))]
+    ModuleNotFound { module: String },
+    #[snafu(display("output port {port} of instance \"{label}\" is already connected"))]
+    OutputConnected { label: String, port: AddressPort },
+    #[snafu(display("port {port} of instance \"{label}\" not found"))]
+    PortNotFound { label: String, port: AddressPort },
+}
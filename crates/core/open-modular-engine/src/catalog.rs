@@ -0,0 +1,232 @@
+//! # Catalog
+//!
+//! The `catalog` module provides a public view of the modules available within
+//! a module enumeration (as generated by the `module_enum` macro). Each entry
+//! in a catalog carries the module id and the full module definition,
+//! including the ordered input and output port definitions.
+//!
+//! A catalog is serializable, and is intended to be consumed by user interfaces
+//! and other tooling (as JSON, for example). It can also generate Markdown
+//! reference pages for each module, based on the module definition.
+
+use std::{
+    fmt::{
+        self,
+        Display,
+        Formatter,
+    },
+    fs,
+    io,
+    path::Path,
+};
+
+use fancy_constructor::new;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use uuid::Uuid;
+
+use crate::module::ModuleDefinition;
+
+// =================================================================================================
+// Catalog
+// =================================================================================================
+
+/// Represents the ability to produce a `Catalog` of all available modules,
+/// generally implemented by a module enumeration.
+pub trait ModuleCatalog {
+    /// Gets a catalog of all modules, in declaration order.
+    fn catalog() -> Catalog;
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Catalog
+
+/// A `Catalog` is an ordered collection of module entries, each of which
+/// pairs a module id with the definition of the module.
+#[derive(new, Clone, Debug, Deserialize, Serialize)]
+pub struct Catalog {
+    /// The catalogued modules
+    pub modules: Vec<CatalogModule>,
+}
+
+impl Catalog {
+    /// Gets the catalogued module with the given id, if present.
+    #[must_use]
+    pub fn get(&self, id: &Uuid) -> Option<&CatalogModule> {
+        self.modules.iter().find(|module| &module.id == id)
+    }
+
+    /// Gets the catalogued module with the given (definition) name, if
+    /// present.
+    #[must_use]
+    pub fn get_by_name(&self, name: &str) -> Option<&CatalogModule> {
+        self.modules
+            .iter()
+            .find(|module| module.definition.name == name)
+    }
+}
+
+impl Catalog {
+    /// Serializes the catalog to a JSON string.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Gets a Markdown reference page for each catalogued module.
+    pub fn pages(&self) -> impl Iterator<Item = CatalogPage<'_>> {
+        self.modules.iter().map(CatalogPage::new)
+    }
+
+    /// Writes a Markdown reference page for each catalogued module to the given
+    /// directory, which will be created if it does not exist. Each page is
+    /// named using the file name given by the page.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be created, or if any page
+    /// cannot be written.
+    pub fn write_pages(&self, directory: impl AsRef<Path>) -> io::Result<()> {
+        let directory = directory.as_ref();
+
+        fs::create_dir_all(directory)?;
+
+        for page in self.pages() {
+            fs::write(directory.join(page.file_name()), page.to_string())?;
+        }
+
+        Ok(())
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Module
+
+/// A `CatalogModule` is a single entry in a catalog, pairing the id of a
+/// module with the definition of the module.
+#[derive(new, Clone, Debug, Deserialize, Serialize)]
+pub struct CatalogModule {
+    /// The unique id of the module
+    pub id: Uuid,
+    /// The definition of the module, including port definitions
+    pub definition: ModuleDefinition,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Page
+
+/// A `CatalogPage` is a Markdown reference page for a single catalogued
+/// module. The Markdown content is produced by the `Display` implementation.
+#[derive(new, Debug)]
+pub struct CatalogPage<'a> {
+    module: &'a CatalogModule,
+}
+
+impl CatalogPage<'_> {
+    /// Gets a file name for the page, derived from the module name (where any
+    /// path separators in the name are replaced).
+    #[must_use]
+    pub fn file_name(&self) -> String {
+        format!("{}.md", self.module.definition.name.replace('/', "-"))
+    }
+}
+
+impl Display for CatalogPage<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let definition = &self.module.definition;
+
+        writeln!(f, "# {}", definition.name)?;
+        writeln!(f)?;
+
+        if let Some(description) = &definition.description {
+            writeln!(f, "{description}")?;
+            writeln!(f)?;
+        }
+
+        writeln!(f, "* Id: `{}`", self.module.id)?;
+        writeln!(f, "* Version: `{}`", definition.version)?;
+
+        if let Some(category) = &definition.category {
+            writeln!(f, "* Category: {category}")?;
+        }
+
+        if !definition.tags.is_empty() {
+            writeln!(f, "* Tags: {}", definition.tags.join(", "))?;
+        }
+
+        if let Some(author) = &definition.author {
+            writeln!(f, "* Author: {author}")?;
+        }
+
+        if let Some(license) = &definition.license {
+            writeln!(f, "* License: {license}")?;
+        }
+
+        writeln!(f)?;
+
+        if let Some(usage) = &definition.usage {
+            writeln!(f, "## Usage")?;
+            writeln!(f)?;
+            writeln!(f, "{}", usage.trim_end())?;
+            writeln!(f)?;
+        }
+
+        let inputs = definition
+            .inputs
+            .iter()
+            .map(|input| (input.name.as_deref(), input.description.as_deref()));
+
+        let outputs = definition
+            .outputs
+            .iter()
+            .map(|output| (output.name.as_deref(), output.description.as_deref()));
+
+        write_ports(f, "Inputs", inputs)?;
+        write_ports(f, "Outputs", outputs)
+    }
+}
+
+fn write_ports<'a>(
+    f: &mut Formatter<'_>,
+    heading: &str,
+    ports: impl ExactSizeIterator<Item = (Option<&'a str>, Option<&'a str>)>,
+) -> fmt::Result {
+    writeln!(f, "## {heading}")?;
+    writeln!(f)?;
+
+    if ports.len() == 0 {
+        writeln!(f, "None.")?;
+        return writeln!(f);
+    }
+
+    writeln!(f, "| Port | Name | Description |")?;
+    writeln!(f, "| ---- | ---- | ----------- |")?;
+
+    for (i, (name, description)) in ports.enumerate() {
+        writeln!(
+            f,
+            "| {i} | {} | {} |",
+            escape_cell(name.unwrap_or_default()),
+            escape_cell(description.unwrap_or_default())
+        )?;
+    }
+
+    writeln!(f)
+}
+
+/// Escapes text for a Markdown table cell, where a `|` would end the cell and
+/// a line break would end the row.
+fn escape_cell(text: &str) -> String {
+    text.trim()
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
@@ -0,0 +1,115 @@
+//! # Denormal
+//!
+//! The `denormal` module provides a guard which enables flush-to-zero (and
+//! denormals-are-zero, where supported) floating point modes on the current
+//! thread for the lifetime of the guard, restoring the previous mode when
+//! dropped. Denormal values are extremely slow to process on most hardware,
+//! and are inaudible, so the processing loop runs within a guard.
+//!
+//! The guard is implemented for `x86_64` (MXCSR FTZ and DAZ flags) and
+//! `aarch64` (FPCR FZ flag) - on other architectures it has no effect.
+
+use std::marker::PhantomData;
+
+// =================================================================================================
+// Denormal
+// =================================================================================================
+
+/// A `DenormalGuard` sets the flush-to-zero floating point mode(s) of the
+/// current thread while it exists. It is not `Send`, as the floating point mode
+/// is thread specific.
+#[derive(Debug)]
+pub struct DenormalGuard {
+    previous: usize,
+    _thread: PhantomData<*const ()>,
+}
+
+impl DenormalGuard {
+    #[must_use]
+    pub fn new() -> Self {
+        let previous = unsafe { get() };
+
+        unsafe {
+            set(previous | FLAGS);
+        }
+
+        Self {
+            previous,
+            _thread: PhantomData,
+        }
+    }
+}
+
+impl Default for DenormalGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for DenormalGuard {
+    fn drop(&mut self) {
+        unsafe {
+            set(self.previous);
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Platform
+
+#[cfg(target_arch = "x86_64")]
+const FLAGS: usize = 0x8040;
+
+#[cfg(target_arch = "x86_64")]
+unsafe fn get() -> usize {
+    let mut mxcsr: u32 = 0;
+
+    unsafe {
+        std::arch::asm!("stmxcsr [{}]", in(reg) &raw mut mxcsr, options(nostack, preserves_flags));
+    }
+
+    mxcsr as usize
+}
+
+#[cfg(target_arch = "x86_64")]
+#[allow(clippy::cast_possible_truncation)]
+unsafe fn set(value: usize) {
+    let mxcsr = value as u32;
+
+    unsafe {
+        std::arch::asm!("ldmxcsr [{}]", in(reg) &raw const mxcsr, options(nostack, readonly, preserves_flags));
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+const FLAGS: usize = 1 << 24;
+
+#[cfg(target_arch = "aarch64")]
+unsafe fn get() -> usize {
+    let fpcr: usize;
+
+    unsafe {
+        std::arch::asm!("mrs {}, fpcr", out(reg) fpcr, options(nomem, nostack, preserves_flags));
+    }
+
+    fpcr
+}
+
+#[cfg(target_arch = "aarch64")]
+unsafe fn set(value: usize) {
+    unsafe {
+        std::arch::asm!("msr fpcr, {}", in(reg) value, options(nomem, nostack, preserves_flags));
+    }
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+const FLAGS: usize = 0;
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+unsafe fn get() -> usize {
+    0
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+unsafe fn set(_value: usize) {}
@@ -0,0 +1,119 @@
+//! # Event
+//!
+//! The `event` module defines a channel for events raised by the engine (the
+//! reverse direction of the protocol bus). Events are sent from the processing
+//! thread, and so the channel is a fixed size, lock-free ring buffer - if the
+//! receiver does not keep up, further events are dropped.
+
+use fancy_constructor::new;
+use rtrb::{
+    Consumer,
+    Producer,
+    PushError,
+    RingBuffer,
+};
+use uuid::Uuid;
+
+// =================================================================================================
+// Event
+// =================================================================================================
+
+#[derive(Clone, Copy, Debug)]
+pub enum Event {
+    Failed(EventFailed),
+    InvalidOutput(EventInvalidOutput),
+}
+
+impl From<EventFailed> for Event {
+    fn from(failed: EventFailed) -> Self {
+        Self::Failed(failed)
+    }
+}
+
+impl From<EventInvalidOutput> for Event {
+    fn from(invalid_output: EventInvalidOutput) -> Self {
+        Self::InvalidOutput(invalid_output)
+    }
+}
+
+// Failed
+
+/// Raised when an instance panics during processing and has been quarantined
+/// (only raised when the processor is in isolation mode).
+#[derive(new, Clone, Copy, Debug)]
+pub struct EventFailed {
+    pub instance: Uuid,
+}
+
+// Invalid Output
+
+/// Raised when an output port of an instance is found to contain invalid
+/// values after processing (only raised when the processor is in debug mode).
+#[derive(new, Clone, Copy, Debug)]
+pub struct EventInvalidOutput {
+    pub instance: Uuid,
+    pub port: usize,
+    pub invalid: EventInvalid,
+}
+
+/// The kind of invalid value found. Where a vector contains more than one kind
+/// of invalid value, the most severe is reported (in the order NaN, infinite,
+/// denormal).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EventInvalid {
+    Denormal,
+    Infinite,
+    NaN,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Events
+
+#[derive(Debug, Default)]
+pub struct Events {}
+
+impl Events {
+    #[must_use]
+    pub fn split(self) -> (EventSender, EventReceiver) {
+        let buffer = RingBuffer::new(256);
+        let sender = EventSender::new(buffer.0);
+        let receiver = EventReceiver::new(buffer.1);
+
+        (sender, receiver)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Receiver
+
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct EventReceiver {
+    consumer: Consumer<Event>,
+}
+
+impl EventReceiver {
+    pub fn receive(&mut self) -> Option<Event> {
+        self.consumer.pop().ok()
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Sender
+
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct EventSender {
+    producer: Producer<Event>,
+}
+
+impl EventSender {
+    pub fn send(&mut self, event: Event) -> Option<Event> {
+        self.producer
+            .push(event)
+            .map_or_else(|PushError::Full(event)| Some(event), |()| None)
+    }
+}
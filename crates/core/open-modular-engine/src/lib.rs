@@ -1,11 +1,20 @@
+#![feature(associated_type_defaults)]
+#![feature(portable_simd)]
 #![feature(sync_unsafe_cell)]
 
+pub mod address;
 pub mod bus;
+pub mod catalog;
 pub mod context;
+pub mod denormal;
+pub mod event;
 pub mod module;
+pub mod patch;
+pub mod path;
 pub mod port;
 pub mod processor;
 pub mod protocol;
+pub mod switch;
 
 // =================================================================================================
 // Compute
@@ -15,5 +24,6 @@ pub mod protocol;
 
 #[doc(hidden)]
 pub mod _dependencies {
+    pub use serde_json;
     pub use uuid;
 }
@@ -1,4 +1,16 @@
+use std::{
+    any::Any,
+    sync::Arc,
+};
+
 use bon::Builder;
+use fancy_constructor::new;
+use serde::{
+    Deserialize,
+    Serialize,
+    de::DeserializeOwned,
+};
+use serde_json::Value;
 use uuid::Uuid;
 
 use crate::{
@@ -21,8 +33,11 @@ use crate::{
 pub trait Module:
       AsMut<PortOutputs>
     + AsRef<PortInputs>
+    + ModuleSnapshot
     + Process
 {
+    /// Gets the definition of the module.
+    fn definition(&self) -> ModuleDefinition;
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -33,16 +48,46 @@ pub trait ModuleDefine {
     fn define(module: ModuleDefinitionBuilder) -> impl Into<ModuleDefinition>;
 }
 
-#[derive(Builder, Debug)]
+#[derive(Builder, Clone, Debug, Deserialize, Serialize)]
 #[builder(derive(Debug), on(String, into))]
 pub struct ModuleDefinition {
     #[builder(field)]
     pub inputs: Vec<PortInputDefinition>,
     #[builder(field)]
     pub outputs: Vec<PortOutputDefinition>,
+    #[builder(field)]
+    pub tags: Vec<String>,
     pub name: String,
+    pub version: Version,
+    pub category: Option<String>,
     pub description: Option<String>,
     pub usage: Option<String>,
+    pub author: Option<String>,
+    pub license: Option<String>,
+    #[builder(with = |input: usize, output: usize| ModuleBypass::new(input, output))]
+    pub bypass: Option<ModuleBypass>,
+    /// Whether the module is a sink (a module which has an effect outside of
+    /// the graph, such as audio output). Only modules which are sinks, or which
+    /// are connected to a sink (directly or indirectly) are processed. Modules
+    /// without any outputs are always treated as sinks.
+    #[builder(default)]
+    #[serde(default)]
+    pub sink: bool,
+    /// Whether the module may sleep when all of the inputs of the module are
+    /// silent, in which case the module is not processed and all outputs are
+    /// silent. Modules which produce output without input (oscillators, or
+    /// modules with internal state such as delay tails) should not enable this.
+    #[builder(default)]
+    #[serde(default)]
+    pub sleep: bool,
+}
+
+/// A `ModuleBypass` declares the input and output ports of a module which are
+/// connected directly when an instance of the module is bypassed.
+#[derive(new, Clone, Debug, Deserialize, Serialize)]
+pub struct ModuleBypass {
+    pub input: usize,
+    pub output: usize,
 }
 
 impl<S> ModuleDefinitionBuilder<S>
@@ -72,6 +117,11 @@ where
         self.outputs.push(definition);
         self
     }
+
+    pub fn with_tag(mut self, tag: impl Into<String>) -> ModuleDefinitionBuilder<S> {
+        self.tags.push(tag.into());
+        self
+    }
 }
 
 impl<S> From<ModuleDefinitionBuilder<S>> for ModuleDefinition
@@ -95,16 +145,147 @@ pub trait ModuleIdentify {
 
 // Instantiate
 
+/// Represents the instantiation of a module, given the context of the runtime
+/// and the instantiation-time configuration of the instance (such as a number
+/// of ports). The configuration is fixed for the lifetime of an instance, and
+/// defaults to `()` for modules which are not configurable.
 pub trait ModuleInstantiate {
     type Context;
+    type Config: Clone + Default + DeserializeOwned + Send + Serialize + Sync + 'static = ();
+
+    /// Gets the definition of the module for the given configuration, where
+    /// the configuration changes the definition (for example by adding ports).
+    /// The `module` macro must be given the `config` argument where a module
+    /// implements this, and the module must then hold the configuration as a
+    /// `config` field. Defaults to the unchanged definition.
+    #[must_use]
+    fn configure(definition: ModuleDefinition, _config: &Self::Config) -> ModuleDefinition {
+        definition
+    }
 
     fn instantiate(
         context: Self::Context,
+        config: Self::Config,
         port_inputs: PortInputs,
         port_outputs: PortOutputs,
     ) -> Self;
 }
 
+/// Represents module configurations and definitions by module id, generally
+/// implemented by a module enumeration (dispatching to the `ModuleInstantiate`
+/// implementation of the relevant module).
+pub trait ModuleConfigureSource {
+    /// Deserializes a configuration for the module with the given id, or
+    /// returns `None` if the module is not found.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration is not a valid configuration for
+    /// the module.
+    fn config(id: &Uuid, config: Value) -> Option<serde_json::Result<ModuleConfig>>;
+
+    /// Gets the definition of the module with the given id for the given
+    /// configuration, or `None` if the module is not found (or the
+    /// configuration is not a configuration of the module).
+    fn configure(id: &Uuid, config: &ModuleConfig) -> Option<ModuleDefinition>;
+}
+
+/// A `ModuleConfig` is an instantiation-time configuration, deserialized as the
+/// configuration type of a module (see `ModuleConfigureSource::config`).
+///
+/// Configurations are deserialized before being sent to the engine, never on
+/// the processing thread, as deserialization may fail, and may be costly (a
+/// configuration may load external resources as part of deserialization).
+/// Configurations are shared, so that protocols holding a configuration can
+/// be cloned.
+#[derive(Clone, Debug)]
+pub struct ModuleConfig(Arc<dyn Any + Send + Sync>);
+
+impl ModuleConfig {
+    #[must_use]
+    pub fn new<C>(config: C) -> Self
+    where
+        C: Send + Sync + 'static,
+    {
+        Self(Arc::new(config))
+    }
+
+    /// Gets a reference to the configuration, if it is a configuration of the
+    /// given type.
+    #[must_use]
+    pub fn get<C>(&self) -> Option<&C>
+    where
+        C: 'static,
+    {
+        self.0.downcast_ref()
+    }
+
+    /// Gets the configuration, if it is a configuration of the given type (the
+    /// configuration is only cloned if it is still shared).
+    #[must_use]
+    pub fn into_inner<C>(self) -> Option<C>
+    where
+        C: Clone + Send + Sync + 'static,
+    {
+        self.0.downcast().ok().map(Arc::unwrap_or_clone)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Migrate
+
+/// Represents the ability of a module to migrate data stored against an older
+/// version of the module (such as port indices or state within a saved patch)
+/// to the current version of the module. All migrations default to the
+/// identity, and a module will only be migrated if it reports that it
+/// migrates from the stored version.
+pub trait ModuleMigrate {
+    /// Returns true if data stored against the given version can be migrated
+    /// to the current version.
+    #[must_use]
+    fn migrates(_version: &Version) -> bool {
+        false
+    }
+
+    /// Migrates an input port index from the given version, returning `None`
+    /// if the port no longer exists.
+    #[must_use]
+    fn migrate_input(_version: &Version, port: usize) -> Option<usize> {
+        Some(port)
+    }
+
+    /// Migrates an output port index from the given version, returning `None`
+    /// if the port no longer exists.
+    #[must_use]
+    fn migrate_output(_version: &Version, port: usize) -> Option<usize> {
+        Some(port)
+    }
+
+    /// Migrates stored state from the given version, returning `None` if the
+    /// state can not be migrated (and should be discarded). Where no state was
+    /// stored, the state is `None`, and a migration may return a state which
+    /// gives the behaviour of the given version (where the defaults of the
+    /// module have since changed).
+    #[must_use]
+    fn migrate_state(_version: &Version, state: Option<Value>) -> Option<Value> {
+        state
+    }
+}
+
+/// Represents module migration by module id, generally implemented by a module
+/// enumeration (dispatching to the `ModuleMigrate` implementation of the
+/// relevant module).
+pub trait ModuleMigrateSource {
+    fn migrates(id: &Uuid, version: &Version) -> bool;
+
+    fn migrate_input(id: &Uuid, version: &Version, port: usize) -> Option<usize>;
+
+    fn migrate_output(id: &Uuid, version: &Version, port: usize) -> Option<usize>;
+
+    fn migrate_state(id: &Uuid, version: &Version, state: Option<Value>) -> Option<Value>;
+}
+
 // -------------------------------------------------------------------------------------------------
 
 // Source
@@ -112,7 +293,98 @@ pub trait ModuleInstantiate {
 pub trait ModuleSource {
     type Context;
 
-    fn get(id: &Uuid, context: Self::Context) -> Self;
+    /// Gets a new instance of the module with the given id, with the given
+    /// configuration (or the default configuration of the module where none is
+    /// given), or `None` if the module is not found, or if the configuration is
+    /// not a configuration of the module. This is called on the processing
+    /// thread, and so must not fail by panicking.
+    fn get(id: &Uuid, context: Self::Context, config: Option<ModuleConfig>) -> Option<Self>
+    where
+        Self: Sized;
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// State
+
+/// Represents a module with internal state (such as oscillator phase) which can
+/// be captured and later restored, for example when saving and loading a
+/// patch. Implementing this trait is optional - where a module implements it,
+/// the `state` argument should be given to the `module` macro, which will then
+/// use it to implement `ModuleSnapshot`.
+pub trait ModuleState {
+    type State: DeserializeOwned + Serialize;
+
+    /// Gets the current state of the module.
+    fn state(&self) -> Self::State;
+
+    /// Restores the module to a previously captured state.
+    fn restore(&mut self, state: Self::State);
+}
+
+/// Represents the capture and restoration of module state as serialized values,
+/// implemented for every module by the `module` macro (based on `ModuleState`
+/// where the module has state) and dispatched by module enumerations.
+pub trait ModuleSnapshot {
+    /// Gets a serialized snapshot of the current state of the module, or `None`
+    /// if the module has no state.
+    fn snapshot(&self) -> Option<Value>;
+
+    /// Restores the module from a serialized snapshot. Modules without state
+    /// ignore the snapshot.
+    ///
+    /// # Panics
+    ///
+    /// Implementations are expected to panic if the snapshot is not a valid
+    /// state for the module.
+    fn restore_snapshot(&mut self, snapshot: Value);
+
+    /// Validates a serialized snapshot without restoring it, so that an invalid
+    /// snapshot (such as a malformed state within a patch file) can be refused
+    /// before it reaches the engine. Modules without state accept any snapshot
+    /// (which is ignored on restoration).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the snapshot is not a valid state for the module.
+    fn validate_snapshot(_snapshot: &Value) -> serde_json::Result<()>
+    where
+        Self: Sized,
+    {
+        Ok(())
+    }
+}
+
+/// Represents the validation of serialized module state by module id,
+/// generally implemented by a module enumeration (dispatching to the
+/// `ModuleSnapshot` implementation of the relevant module).
+pub trait ModuleSnapshotSource {
+    /// Validates a serialized snapshot for the module with the given id, or
+    /// returns `None` if the module is not found.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the snapshot is not a valid state for the module.
+    fn validate(id: &Uuid, snapshot: &Value) -> Option<serde_json::Result<()>>;
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Version
+
+pub use semver::Version;
+
+/// Returns true if data saved against the `saved` version of a module (such as
+/// an instance within a patch) is compatible with the `current` version of the
+/// module. Following semver, versions are compatible if the major versions are
+/// the same, except that before 1.0.0 (where any minor version may be a
+/// breaking change) the minor versions must also be the same.
+#[must_use]
+pub fn is_compatible(saved: &Version, current: &Version) -> bool {
+    match (saved.major, current.major) {
+        (0, 0) => saved.minor == current.minor,
+        (major, current) => major == current,
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -0,0 +1,896 @@
+//! # Patch
+//!
+//! The `patch` module defines a serializable representation of a patch - a set
+//! of module instances and the connections between them. A patch records the
+//! version of each module at the time the patch was saved, so that loading a
+//! patch can refuse modules which are no longer compatible.
+//!
+//! Loading a patch is intended to happen outside of the engine. All validation
+//! happens at load time (in line with the engine expectation that any
+//! instruction it receives is expected to succeed), and a successfully loaded
+//! patch is returned as a sequence of protocol messages to be sent to the
+//! engine.
+//!
+//! A patch may also define sub-patches - reusable groups of instances and
+//! connections, packaged as a single module with exposed (boundary) input and
+//! output ports. An instance of a sub-patch is expanded into the constituent
+//! instances when the patch is loaded, each addressed by an `InstancePath`
+//! (see the `path` module). Boundary ports are forwarded by connecting
+//! directly to the relevant inner port, so crossing a sub-patch boundary has
+//! no cost. Sub-patches may be nested, and may use any sub-patch defined in an
+//! enclosing patch.
+
+use std::{
+    borrow::Cow,
+    collections::{
+        HashMap,
+        HashSet,
+    },
+};
+
+use fancy_constructor::new;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::{
+    Map,
+    Value,
+};
+use snafu::{
+    OptionExt as _,
+    ResultExt as _,
+    Snafu,
+    ensure,
+};
+use uuid::Uuid;
+
+use crate::{
+    catalog::Catalog,
+    module::{
+        ModuleConfigureSource,
+        ModuleDefinition,
+        ModuleMigrateSource,
+        ModuleSnapshotSource,
+        Version,
+        is_compatible,
+    },
+    path::InstancePath,
+    port::PortIndex,
+    protocol::{
+        Protocol,
+        ProtocolAdd,
+        ProtocolConnect,
+        ProtocolRestore,
+    },
+};
+
+// =================================================================================================
+// Patch
+// =================================================================================================
+
+/// A `Patch` is a set of module instances, and the connections between the
+/// ports of those instances.
+#[derive(new, Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Patch {
+    /// The module instances within the patch
+    pub instances: Vec<PatchInstance>,
+    /// The connections between the ports of module instances
+    pub connections: Vec<PatchConnection>,
+    /// The sub-patches defined by the patch, available to instances within the
+    /// patch (and within any sub-patch)
+    #[new(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sub_patches: Vec<SubPatch>,
+}
+
+impl Patch {
+    /// Migrates the patch against the given catalog, upgrading any instance
+    /// saved against an older version of a module to the current version of
+    /// the module (where the module reports that it can be migrated from the
+    /// saved version). Port indices of connections and any stored state are
+    /// migrated using the `ModuleMigrate` implementation of the module, and any
+    /// connection to a port which no longer exists is dropped.
+    ///
+    /// Instances which are not migrated are left unchanged (and may then be
+    /// refused when the patch is loaded). Sub-patch definitions are migrated in
+    /// the same way, along with the stored state of instances nested within
+    /// sub-patch instances.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any instance refers to a module which is not found.
+    pub fn migrate<M>(self, catalog: &Catalog) -> Result<Self, PatchError>
+    where
+        M: ModuleMigrateSource,
+    {
+        let mut sub_patches = HashMap::new();
+
+        SubPatch::migrations::<M>(&self.sub_patches, catalog, &mut sub_patches);
+
+        self.migrate_with::<M>(catalog, &sub_patches)
+    }
+
+    fn migrate_with<M>(
+        self,
+        catalog: &Catalog,
+        sub_patches: &SubPatchMigrations,
+    ) -> Result<Self, PatchError>
+    where
+        M: ModuleMigrateSource,
+    {
+        let mut migrations = HashMap::new();
+        let mut instances = Vec::with_capacity(self.instances.len());
+
+        for mut instance in self.instances {
+            if let Some(inner) = sub_patches.get(&instance.module) {
+                instance.state = migrate_nested::<M>(inner, sub_patches, instance.state);
+
+                instances.push(instance);
+
+                continue;
+            }
+
+            let module = catalog.get(&instance.module).context(ModuleNotFoundError {
+                instance: instance.instance,
+                module: instance.module,
+            })?;
+
+            let current = &module.definition.version;
+
+            if &instance.version < current && M::migrates(&instance.module, &instance.version) {
+                instance.state =
+                    M::migrate_state(&instance.module, &instance.version, instance.state);
+
+                migrations.insert(instance.instance, (instance.module, instance.version));
+                instance.version = current.clone();
+            }
+
+            instances.push(instance);
+        }
+
+        let connections = self
+            .connections
+            .into_iter()
+            .filter_map(|mut connection| {
+                if let Some((module, version)) = migrations.get(&connection.input_instance) {
+                    connection.input_port =
+                        M::migrate_input(module, version, connection.input_port)?;
+                }
+
+                if let Some((module, version)) = migrations.get(&connection.output_instance) {
+                    connection.output_port =
+                        M::migrate_output(module, version, connection.output_port)?;
+                }
+
+                Some(connection)
+            })
+            .collect();
+
+        let sub_patches = self
+            .sub_patches
+            .into_iter()
+            .map(|sub_patch| sub_patch.migrate_with::<M>(catalog, sub_patches))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            instances,
+            connections,
+            sub_patches,
+        })
+    }
+
+    /// Sets the stored state of instances within the patch from a snapshot of
+    /// instance states (such as that returned by a snapshot of a processor).
+    /// States for instances which are not part of the patch are ignored. The
+    /// stored state of a sub-patch instance is an object containing the states
+    /// of the instances within the sub-patch, keyed by instance id.
+    pub fn set_states(&mut self, states: impl IntoIterator<Item = (Uuid, Value)>) {
+        let states = states.into_iter().collect::<HashMap<_, _>>();
+        let scope = self.sub_patches.iter().collect::<Vec<_>>();
+
+        for instance in &mut self.instances {
+            let path = InstancePath::from(instance.instance);
+
+            if let Some(state) =
+                nested_state(&scope, &mut Vec::new(), instance.module, &path, &states)
+            {
+                instance.state = Some(state);
+            }
+        }
+    }
+
+    /// Loads the patch against the given catalog, validating that every
+    /// instance is unique and refers to a known module at a compatible version,
+    /// and that every connection refers to instances and ports which exist
+    /// (and which are not already connected), and that any stored state is a
+    /// valid state for the module. The patch is returned as a sequence of
+    /// protocol messages (instances are added, and any stored state restored,
+    /// before any connections are made).
+    ///
+    /// A module is compatible if the version of the module in the catalog is
+    /// compatible with the version recorded in the patch (see `is_compatible`,
+    /// under which the major versions must match, as must the minor versions
+    /// before 1.0.0). The same applies to sub-patches, based on the version in
+    /// the sub-patch definition. The ports of an instance with a stored
+    /// configuration are validated against the definition of the module for
+    /// that configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any instance or connection fails validation.
+    pub fn load<M>(self, catalog: &Catalog) -> Result<Vec<Protocol>, PatchError>
+    where
+        M: ModuleConfigureSource + ModuleSnapshotSource,
+    {
+        let mut protocols = Vec::with_capacity(self.instances.len() + self.connections.len());
+
+        self.expand::<M>(
+            catalog,
+            &[],
+            &mut Vec::new(),
+            &InstancePath::default(),
+            None,
+            &mut HashSet::new(),
+            &mut protocols,
+        )?;
+
+        Ok(protocols)
+    }
+
+    /// Expands the patch (with instance ids relative to the given path) into
+    /// protocol messages, returning the expanded instances of the patch, keyed
+    /// by instance id. Where states are given (for instances within a
+    /// sub-patch instance), they take precedence over any state stored in the
+    /// patch itself. The ports connected so far (across all expanded patches,
+    /// as a boundary port is connected as the inner port to which it is
+    /// forwarded) are recorded, so that no port is connected twice.
+    #[allow(clippy::too_many_arguments)]
+    fn expand<'a, M>(
+        &'a self,
+        catalog: &'a Catalog,
+        outer: &[&'a SubPatch],
+        active: &mut Vec<Uuid>,
+        path: &InstancePath,
+        states: Option<&Map<String, Value>>,
+        connected: &mut HashSet<(Uuid, PortIndex)>,
+        protocols: &mut Vec<Protocol>,
+    ) -> Result<HashMap<Uuid, Expanded<'a>>, PatchError>
+    where
+        M: ModuleConfigureSource + ModuleSnapshotSource,
+    {
+        let scope = outer
+            .iter()
+            .copied()
+            .chain(&self.sub_patches)
+            .collect::<Vec<_>>();
+
+        let mut expanded = HashMap::with_capacity(self.instances.len());
+
+        for instance in &self.instances {
+            ensure!(
+                !expanded.contains_key(&instance.instance),
+                DuplicateInstanceError {
+                    instance: instance.instance
+                }
+            );
+
+            let instance_path = path.join(instance.instance);
+            let id = instance_path.id();
+            let state = states
+                .and_then(|states| states.get(&instance.instance.to_string()).cloned())
+                .or_else(|| instance.state.clone());
+
+            if let Some(module) = catalog.get(&instance.module) {
+                instance.ensure_compatible(&module.definition)?;
+
+                let mut add = ProtocolAdd::new(id, instance.module);
+
+                let definition = match &instance.config {
+                    Some(config) => {
+                        let config = M::config(&instance.module, config.clone())
+                            .context(ModuleNotFoundError {
+                                instance: instance.instance,
+                                module: instance.module,
+                            })?
+                            .context(ConfigError {
+                                instance: instance.instance,
+                                module: instance.module,
+                            })?;
+
+                        let definition = M::configure(&instance.module, &config).context(
+                            ModuleNotFoundError {
+                                instance: instance.instance,
+                                module: instance.module,
+                            },
+                        )?;
+
+                        add = add.with_config(config);
+
+                        Cow::Owned(definition)
+                    }
+                    None => Cow::Borrowed(&module.definition),
+                };
+
+                protocols.push(add.into());
+
+                if let Some(state) = state {
+                    M::validate(&instance.module, &state)
+                        .context(ModuleNotFoundError {
+                            instance: instance.instance,
+                            module: instance.module,
+                        })?
+                        .context(StateError {
+                            instance: instance.instance,
+                            module: instance.module,
+                        })?;
+
+                    protocols.push(ProtocolRestore::new(id, state).into());
+                }
+
+                expanded.insert(
+                    instance.instance,
+                    Expanded::Module(id, Box::new(definition)),
+                );
+            } else if let Some(sub_patch) = find(&scope, instance.module) {
+                instance.ensure_compatible(&sub_patch.definition)?;
+
+                ensure!(!active.contains(&sub_patch.id), SubPatchCycleError {
+                    instance: instance.instance,
+                    module: sub_patch.id,
+                });
+
+                ensure!(
+                    sub_patch.inputs.len() == sub_patch.definition.inputs.len()
+                        && sub_patch.outputs.len() == sub_patch.definition.outputs.len(),
+                    SubPatchPortsError {
+                        module: sub_patch.id,
+                    }
+                );
+
+                active.push(sub_patch.id);
+
+                let inner = sub_patch.patch.expand::<M>(
+                    catalog,
+                    &scope,
+                    active,
+                    &instance_path,
+                    state.as_ref().and_then(Value::as_object),
+                    connected,
+                    protocols,
+                )?;
+
+                active.pop();
+
+                expanded.insert(instance.instance, Expanded::SubPatch(sub_patch, inner));
+            } else {
+                return ModuleNotFoundError {
+                    instance: instance.instance,
+                    module: instance.module,
+                }
+                .fail();
+            }
+        }
+
+        for connection in &self.connections {
+            protocols.push(connect(&expanded, connection, connected)?.into());
+        }
+
+        Ok(expanded)
+    }
+}
+
+/// An instance of a patch after expansion, either a module instance (with the
+/// expanded id of the instance), or a sub-patch instance (with the expanded
+/// instances of the sub-patch).
+enum Expanded<'a> {
+    Module(Uuid, Box<Cow<'a, ModuleDefinition>>),
+    SubPatch(&'a SubPatch, HashMap<Uuid, Expanded<'a>>),
+}
+
+/// Resolves a port of an expanded instance to the id and port index of the
+/// module instance which owns the port, following boundary ports of sub-patch
+/// instances to the inner ports to which they are forwarded.
+fn resolve(
+    expanded: &HashMap<Uuid, Expanded<'_>>,
+    instance: Uuid,
+    port: PortIndex,
+) -> Result<(Uuid, usize), PatchError> {
+    let (PortIndex::Input(index) | PortIndex::Output(index)) = port;
+
+    match expanded
+        .get(&instance)
+        .context(InstanceNotFoundError { instance })?
+    {
+        Expanded::Module(id, definition) => {
+            let ports = match port {
+                PortIndex::Input(_) => definition.inputs.len(),
+                PortIndex::Output(_) => definition.outputs.len(),
+            };
+
+            ensure!(index < ports, PortNotFoundError {
+                instance,
+                port: index
+            });
+
+            Ok((*id, index))
+        }
+        Expanded::SubPatch(sub_patch, inner) => {
+            let boundary = sub_patch.boundary(port).context(PortNotFoundError {
+                instance,
+                port: index,
+            })?;
+
+            let port = match port {
+                PortIndex::Input(_) => PortIndex::Input(boundary.port),
+                PortIndex::Output(_) => PortIndex::Output(boundary.port),
+            };
+
+            resolve(inner, boundary.instance, port)
+        }
+    }
+}
+
+/// Resolves both ports of a connection between expanded instances, recording
+/// the resolved ports as connected (neither port may already be connected).
+fn connect(
+    expanded: &HashMap<Uuid, Expanded<'_>>,
+    connection: &PatchConnection,
+    connected: &mut HashSet<(Uuid, PortIndex)>,
+) -> Result<ProtocolConnect, PatchError> {
+    let (input_instance, input_port) = resolve(
+        expanded,
+        connection.input_instance,
+        PortIndex::Input(connection.input_port),
+    )?;
+
+    let (output_instance, output_port) = resolve(
+        expanded,
+        connection.output_instance,
+        PortIndex::Output(connection.output_port),
+    )?;
+
+    ensure!(
+        connected.insert((input_instance, PortIndex::Input(input_port))),
+        InputConnectedError {
+            instance: connection.input_instance,
+            port: connection.input_port,
+        }
+    );
+
+    ensure!(
+        connected.insert((output_instance, PortIndex::Output(output_port))),
+        OutputConnectedError {
+            instance: connection.output_instance,
+            port: connection.output_port,
+        }
+    );
+
+    Ok(ProtocolConnect::new(
+        input_instance,
+        input_port,
+        output_instance,
+        output_port,
+    ))
+}
+
+/// Finds a sub-patch within a scope, where later (inner) definitions take
+/// precedence over earlier (outer) definitions.
+fn find<'a>(scope: &[&'a SubPatch], id: Uuid) -> Option<&'a SubPatch> {
+    scope
+        .iter()
+        .rev()
+        .find(|sub_patch| sub_patch.id == id)
+        .copied()
+}
+
+/// Gets the state of an instance from a snapshot of instance states, where the
+/// state of a sub-patch instance is the object of the states of the instances
+/// within the sub-patch (if any have state).
+fn nested_state(
+    scope: &[&SubPatch],
+    active: &mut Vec<Uuid>,
+    module: Uuid,
+    path: &InstancePath,
+    states: &HashMap<Uuid, Value>,
+) -> Option<Value> {
+    let Some(sub_patch) = find(scope, module) else {
+        return states.get(&path.id()).cloned();
+    };
+
+    if active.contains(&sub_patch.id) {
+        return None;
+    }
+
+    let scope = scope
+        .iter()
+        .copied()
+        .chain(&sub_patch.patch.sub_patches)
+        .collect::<Vec<_>>();
+
+    active.push(sub_patch.id);
+
+    let inner = sub_patch
+        .patch
+        .instances
+        .iter()
+        .filter_map(|instance| {
+            let path = path.join(instance.instance);
+            let state = nested_state(&scope, active, instance.module, &path, states)?;
+
+            Some((instance.instance.to_string(), state))
+        })
+        .collect::<Map<_, _>>();
+
+    active.pop();
+
+    (!inner.is_empty()).then_some(Value::Object(inner))
+}
+
+/// Migrates the stored state of a sub-patch instance (an object of the states
+/// of the instances within the sub-patch, keyed by instance id), given the
+/// migrations of the instances within the sub-patch. Every migrated instance is
+/// migrated, whether or not a state was stored for it (see `migrate_state`).
+fn migrate_nested<M>(
+    migrations: &HashMap<Uuid, SubPatchMigration>,
+    sub_patches: &SubPatchMigrations,
+    state: Option<Value>,
+) -> Option<Value>
+where
+    M: ModuleMigrateSource,
+{
+    let mut states = match state {
+        Some(Value::Object(states)) => states,
+        Some(state) => return Some(state),
+        None => Map::new(),
+    };
+
+    for (instance, migration) in migrations {
+        let key = instance.to_string();
+        let state = states.remove(&key);
+
+        let state = match migration {
+            SubPatchMigration::Module(module, version) => M::migrate_state(module, version, state),
+            SubPatchMigration::SubPatch(module) => match sub_patches.get(module) {
+                Some(inner) => migrate_nested::<M>(inner, sub_patches, state),
+                None => state,
+            },
+        };
+
+        if let Some(state) = state {
+            states.insert(key, state);
+        }
+    }
+
+    (!states.is_empty()).then_some(Value::Object(states))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Instance
+
+/// A `PatchInstance` is a single module instance within a patch, recording the
+/// version of the module which was current when the patch was saved.
+#[derive(new, Clone, Debug, Deserialize, Serialize)]
+pub struct PatchInstance {
+    /// The unique id of the instance
+    pub instance: Uuid,
+    /// The unique id of the module
+    pub module: Uuid,
+    /// The version of the module when the patch was saved
+    pub version: Version,
+    /// The user-assigned label of the instance, if any (see the `address`
+    /// module)
+    #[new(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// The instantiation-time configuration of the instance, if any (where
+    /// the instance does not have the default configuration of the module)
+    #[new(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<Value>,
+    /// The stored state of the instance, if any
+    #[new(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<Value>,
+}
+
+impl PatchInstance {
+    fn ensure_compatible(&self, definition: &ModuleDefinition) -> Result<(), PatchError> {
+        ensure!(
+            is_compatible(&self.version, &definition.version),
+            IncompatibleVersionError {
+                instance: self.instance,
+                module: self.module,
+                saved: self.version.clone(),
+                current: definition.version.clone(),
+            }
+        );
+
+        Ok(())
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Connection
+
+/// A `PatchConnection` is a connection from an output port of one instance to
+/// an input port of another (or the same) instance.
+#[derive(new, Clone, Debug, Deserialize, Serialize)]
+pub struct PatchConnection {
+    /// The unique id of the instance of the input port
+    pub input_instance: Uuid,
+    /// The index of the input port
+    pub input_port: usize,
+    /// The unique id of the instance of the output port
+    pub output_instance: Uuid,
+    /// The index of the output port
+    pub output_port: usize,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Sub-Patch
+
+/// A `SubPatch` is a reusable patch, packaged as a module. The sub-patch has a
+/// module definition (with a unique id, used by instances of the sub-patch in
+/// place of a module id), where the input and output ports of the definition
+/// are boundary ports, each forwarded to a port of an instance within the
+/// sub-patch.
+///
+/// Any state stored against instances within the patch of a sub-patch is used
+/// as the initial state of those instances (unless overridden by state stored
+/// against the instance of the sub-patch).
+#[derive(new, Clone, Debug, Deserialize, Serialize)]
+pub struct SubPatch {
+    /// The unique id of the sub-patch (equivalent to a module id)
+    pub id: Uuid,
+    /// The definition of the sub-patch, including the boundary ports
+    pub definition: ModuleDefinition,
+    /// The inner port to which each boundary input port is forwarded
+    pub inputs: Vec<SubPatchPort>,
+    /// The inner port to which each boundary output port is forwarded
+    pub outputs: Vec<SubPatchPort>,
+    /// The instances and connections within the sub-patch
+    pub patch: Patch,
+}
+
+impl SubPatch {
+    /// Loads a single instance of the sub-patch against the given catalog, as
+    /// for `Patch::load`. Connections to the boundary ports of the new instance
+    /// can then be made using the ports given by `SubPatch::input` and
+    /// `SubPatch::output`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sub-patch fails validation.
+    pub fn load<M>(&self, instance: Uuid, catalog: &Catalog) -> Result<Vec<Protocol>, PatchError>
+    where
+        M: ModuleConfigureSource + ModuleSnapshotSource,
+    {
+        let instances = vec![PatchInstance::new(
+            instance,
+            self.id,
+            self.definition.version.clone(),
+        )];
+
+        let mut patch = Patch::new(instances, Vec::new());
+
+        patch.sub_patches.push(self.clone());
+        patch.load::<M>(catalog)
+    }
+
+    /// Gets the path (relative to an instance of the sub-patch) of the instance
+    /// and the port index to which the given boundary input port is forwarded
+    /// (following any nested sub-patches defined within the sub-patch).
+    #[must_use]
+    pub fn input(&self, port: usize) -> Option<(InstancePath, usize)> {
+        self.forward(PortIndex::Input(port), &[], &mut Vec::new())
+    }
+
+    /// Gets the path (relative to an instance of the sub-patch) of the instance
+    /// and the port index to which the given boundary output port is forwarded
+    /// (following any nested sub-patches defined within the sub-patch).
+    #[must_use]
+    pub fn output(&self, port: usize) -> Option<(InstancePath, usize)> {
+        self.forward(PortIndex::Output(port), &[], &mut Vec::new())
+    }
+
+    fn boundary(&self, port: PortIndex) -> Option<&SubPatchPort> {
+        match port {
+            PortIndex::Input(port) => self.inputs.get(port),
+            PortIndex::Output(port) => self.outputs.get(port),
+        }
+    }
+
+    fn forward<'a>(
+        &'a self,
+        port: PortIndex,
+        outer: &[&'a SubPatch],
+        active: &mut Vec<Uuid>,
+    ) -> Option<(InstancePath, usize)> {
+        if active.contains(&self.id) {
+            return None;
+        }
+
+        let boundary = self.boundary(port)?;
+        let instance = self
+            .patch
+            .instances
+            .iter()
+            .find(|instance| instance.instance == boundary.instance)?;
+
+        let scope = outer
+            .iter()
+            .copied()
+            .chain(&self.patch.sub_patches)
+            .collect::<Vec<_>>();
+
+        let path = InstancePath::from(boundary.instance);
+
+        let Some(sub_patch) = find(&scope, instance.module) else {
+            return Some((path, boundary.port));
+        };
+
+        let port = match port {
+            PortIndex::Input(_) => PortIndex::Input(boundary.port),
+            PortIndex::Output(_) => PortIndex::Output(boundary.port),
+        };
+
+        active.push(self.id);
+
+        let (inner, port) = sub_patch.forward(port, &scope, active)?;
+
+        active.pop();
+
+        Some((InstancePath::new([path.0, inner.0].concat()), port))
+    }
+}
+
+impl SubPatch {
+    /// Collects the migrations which will apply to the instances of each of
+    /// the given sub-patches (and any sub-patches nested within them), before
+    /// any migration takes place.
+    fn migrations<M>(
+        sub_patches: &[SubPatch],
+        catalog: &Catalog,
+        migrations: &mut SubPatchMigrations,
+    ) where
+        M: ModuleMigrateSource,
+    {
+        for sub_patch in sub_patches {
+            Self::migrations::<M>(&sub_patch.patch.sub_patches, catalog, migrations);
+
+            let instances = sub_patch
+                .patch
+                .instances
+                .iter()
+                .filter_map(|instance| match catalog.get(&instance.module) {
+                    Some(module) => (instance.version < module.definition.version
+                        && M::migrates(&instance.module, &instance.version))
+                    .then(|| {
+                        let migration =
+                            SubPatchMigration::Module(instance.module, instance.version.clone());
+
+                        (instance.instance, migration)
+                    }),
+                    None => Some((
+                        instance.instance,
+                        SubPatchMigration::SubPatch(instance.module),
+                    )),
+                })
+                .collect();
+
+            migrations.insert(sub_patch.id, instances);
+        }
+    }
+
+    fn migrate_with<M>(
+        mut self,
+        catalog: &Catalog,
+        sub_patches: &SubPatchMigrations,
+    ) -> Result<Self, PatchError>
+    where
+        M: ModuleMigrateSource,
+    {
+        if let Some(migrations) = sub_patches.get(&self.id) {
+            for (boundary, input) in self
+                .inputs
+                .iter_mut()
+                .map(|boundary| (boundary, true))
+                .chain(self.outputs.iter_mut().map(|boundary| (boundary, false)))
+            {
+                if let Some(SubPatchMigration::Module(module, version)) =
+                    migrations.get(&boundary.instance)
+                {
+                    let port = if input {
+                        M::migrate_input(module, version, boundary.port)
+                    } else {
+                        M::migrate_output(module, version, boundary.port)
+                    };
+
+                    // A boundary port can not be removed without changing the
+                    // definition of the sub-patch, so a port which no longer
+                    // exists is left unchanged (and refused on load).
+
+                    if let Some(port) = port {
+                        boundary.port = port;
+                    }
+                }
+            }
+        }
+
+        self.patch = self.patch.migrate_with::<M>(catalog, sub_patches)?;
+
+        Ok(self)
+    }
+}
+
+/// A `SubPatchPort` identifies the port of an instance within a sub-patch to
+/// which a boundary port is forwarded.
+#[derive(new, Clone, Debug, Deserialize, Serialize)]
+pub struct SubPatchPort {
+    /// The unique id of the instance within the sub-patch
+    pub instance: Uuid,
+    /// The index of the port
+    pub port: usize,
+}
+
+/// The migrations which will apply to the instances within each sub-patch,
+/// keyed by sub-patch id.
+type SubPatchMigrations = HashMap<Uuid, HashMap<Uuid, SubPatchMigration>>;
+
+/// The migration of an instance within a sub-patch, either a module instance
+/// migrated from a saved version, or an instance of a (nested) sub-patch.
+enum SubPatchMigration {
+    Module(Uuid, Version),
+    SubPatch(Uuid),
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Error
+
+#[derive(Debug, Snafu)]
+#[snafu(context(suffix(Error)), visibility(pub(crate)))]
+pub enum PatchError {
+    #[snafu(display("module {module} (instance {instance}) config is invalid"))]
+    Config {
+        instance: Uuid,
+        module: Uuid,
+        source: serde_json::Error,
+    },
+    #[snafu(display("instance {instance} is not unique"))]
+    DuplicateInstance { instance: Uuid },
+    #[snafu(display(
+        "module {module} (instance {instance}) saved at version {saved} is incompatible with \
+         version {current}"
+    ))]
+    IncompatibleVersion {
+        instance: Uuid,
+        module: Uuid,
+        saved: Version,
+        current: Version,
+    },
+    #[snafu(display("input port {port} of instance {instance} is already connected"))]
+    InputConnected { instance: Uuid, port: usize },
+    #[snafu(display("instance {instance} not found"))]
+    InstanceNotFound { instance: Uuid },
+    #[snafu(display("module {module} (instance {instance}) not found"))]
+    ModuleNotFound { instance: Uuid, module: Uuid },
+    #[snafu(display("output port {port} of instance {instance} is already connected"))]
+    OutputConnected { instance: Uuid, port: usize },
+    #[snafu(display("port {port} of instance {instance} not found"))]
+    PortNotFound { instance: Uuid, port: usize },
+    #[snafu(display("module {module} (instance {instance}) state is invalid"))]
+    State {
+        instance: Uuid,
+        module: Uuid,
+        source: serde_json::Error,
+    },
+    #[snafu(display("sub-patch {module} (instance {instance}) contains itself"))]
+    SubPatchCycle { instance: Uuid, module: Uuid },
+    #[snafu(display("sub-patch {module} boundary ports do not match definition"))]
+    SubPatchPorts { module: Uuid },
+}
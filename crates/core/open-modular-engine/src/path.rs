@@ -0,0 +1,124 @@
+//! # Path
+//!
+//! The `path` module defines paths for addressing instances nested within
+//! sub-patches. Sub-patches are expanded into their constituent instances when
+//! loaded, so the engine itself only sees single instances - the id of a nested
+//! instance is derived deterministically from the path of instance ids leading
+//! to it (the instance id of each containing sub-patch, followed by the id of
+//! the instance within the innermost sub-patch).
+//!
+//! A path with a single element addresses a top-level instance, and has the id
+//! of that instance, so top-level instances are addressed as normal. Paths can
+//! be used wherever the control protocol expects an instance id.
+
+use std::fmt::{
+    self,
+    Display,
+    Formatter,
+};
+
+use fancy_constructor::new;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use uuid::{
+    Builder,
+    Uuid,
+};
+
+// =================================================================================================
+// Path
+// =================================================================================================
+
+/// An `InstancePath` is a sequence of instance ids, addressing an instance
+/// nested within zero or more sub-patches.
+#[derive(new, Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct InstancePath(pub Vec<Uuid>);
+
+impl InstancePath {
+    /// Gets a new path, extending this path with the given instance id.
+    #[must_use]
+    pub fn join(&self, instance: Uuid) -> Self {
+        let mut path = self.0.clone();
+
+        path.push(instance);
+
+        Self::new(path)
+    }
+
+    /// Gets the id of the addressed instance within the processor. The id of
+    /// an empty path is the nil id.
+    #[must_use]
+    pub fn id(&self) -> Uuid {
+        let mut path = self.0.iter();
+
+        path.next().map_or_else(Uuid::nil, |first| {
+            path.fold(*first, |parent, child| derive(parent, *child))
+        })
+    }
+}
+
+impl Display for InstancePath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, instance) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, "/")?;
+            }
+
+            write!(f, "{instance}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<Uuid> for InstancePath {
+    fn from(instance: Uuid) -> Self {
+        Self::new(vec![instance])
+    }
+}
+
+impl From<InstancePath> for Uuid {
+    fn from(path: InstancePath) -> Self {
+        path.id()
+    }
+}
+
+impl From<&InstancePath> for Uuid {
+    fn from(path: &InstancePath) -> Self {
+        path.id()
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Derive
+
+/// Derives the id of a nested instance from the id of the containing sub-patch
+/// instance and the id of the instance within the sub-patch. The derived id is
+/// a version 8 (custom) UUID, built from a non-cryptographic mix of the two
+/// ids.
+#[must_use]
+pub fn derive(parent: Uuid, child: Uuid) -> Uuid {
+    let (parent_high, parent_low) = parent.as_u64_pair();
+    let (child_high, child_low) = child.as_u64_pair();
+
+    let high = mix(parent_high ^ mix(child_high ^ parent_low));
+    let low = mix(parent_low ^ mix(child_low ^ high));
+
+    let mut bytes = [0; 16];
+
+    bytes[..8].copy_from_slice(&high.to_be_bytes());
+    bytes[8..].copy_from_slice(&low.to_be_bytes());
+
+    Builder::from_custom_bytes(bytes).into_uuid()
+}
+
+// SplitMix64 finalizer
+const fn mix(value: u64) -> u64 {
+    let value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    let value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+
+    value ^ (value >> 31)
+}
@@ -16,16 +16,28 @@
 //! logical iteration, a module will write to one vector, while the input reads
 //! from the other, making read/write logic effectively striped. This should
 //! eliminate the need for locking, provided that this access pattern is
-//! maintained.
+//! maintained. References are only ever taken to a single vector (never to the
+//! pair), so that a reader of one vector and the writer of the other never
+//! hold overlapping references.
+//!
+//! The vectors of connected output ports are not allocated individually, but
+//! are taken from a `PortArena` owned by the processor, which allocates
+//! contiguous, cache-aligned blocks of buffers up front (and returns buffers to
+//! the arena on disconnection).
 
 use std::{
     cell::SyncUnsafeCell,
+    ptr::NonNull,
     sync::Arc,
 };
 
 use bon::Builder;
 use fancy_constructor::new;
 use open_modular_core::Vector;
+use serde::{
+    Deserialize,
+    Serialize,
+};
 
 use crate::{
     module::ModuleDefinition,
@@ -56,21 +68,36 @@ impl<T> Default for Port<T> {
 
 // -------------------------------------------------------------------------------------------------
 
+// Index
+
+/// `PortIndex` identifies a single port of a module, as either an input or an
+/// output port at a given (positional) index.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PortIndex {
+    Input(usize),
+    Output(usize),
+}
+
+// -------------------------------------------------------------------------------------------------
+
 // Connect
 
 /// Represents logical port connection, where self is expected to be either an
 /// input or output port, and other is expected to be the opposing port type.
 pub(crate) trait PortConnect<P> {
     /// Connects two logical ports, of types defined by the parameterisation of
-    /// the trait. See documentation on concrete implementations for relevant
-    /// implemehtation detail or constraints.
-    unsafe fn connect(&self, other: &P);
+    /// the trait, using the given buffer to hold connection data. See
+    /// documentation on concrete implementations for relevant implemehtation
+    /// detail or constraints.
+    unsafe fn connect(&self, other: &P, buffer: PortBuffer);
 }
 
 /// Represents logical port connection from output to input.
 impl PortConnect<Arc<SyncUnsafeCell<PortInput>>> for Arc<SyncUnsafeCell<PortOutput>> {
     /// Connects a `PortOutput` to a `PortInput` (where both are provided as
-    /// `Arc` smart pointers to a `SyncUnsafeCell` containing the port).
+    /// `Arc` smart pointers to a `SyncUnsafeCell` containing the port). The
+    /// output port takes ownership of the buffer (which is expected to have
+    /// been obtained from a `PortArena`).
     ///
     /// # Panics
     ///
@@ -85,13 +112,13 @@ impl PortConnect<Arc<SyncUnsafeCell<PortInput>>> for Arc<SyncUnsafeCell<PortOutp
     /// single thread (or where it can be shown that any pairs of output/input
     /// ports are always disjoint - e.g. calling Output A -> Input B and Output
     /// C -> Input D would be safe).
-    unsafe fn connect(&self, input: &Arc<SyncUnsafeCell<PortInput>>) {
+    unsafe fn connect(&self, input: &Arc<SyncUnsafeCell<PortInput>>, buffer: PortBuffer) {
         let output = unsafe { &mut (*self.get()) };
         let input = unsafe { &mut (*input.get()) };
 
         match (&output, &input) {
             (Port::Disconnected, Port::Disconnected) => {
-                *output = Port::Connected(Box::default());
+                *output = Port::Connected(buffer);
                 *input = Port::Connected(Arc::clone(self));
             }
             (Port::Connected(_), Port::Connected(_)) => panic!("output and input connected"),
@@ -110,8 +137,10 @@ impl PortConnect<Arc<SyncUnsafeCell<PortInput>>> for Arc<SyncUnsafeCell<PortOutp
 /// logical connection.
 pub(crate) trait PortDisconnect {
     /// Disconnect, which should result in both self AND the other relevant port
-    /// being in the disconnected state after calling.
-    unsafe fn disconnect(&self);
+    /// being in the disconnected state after calling. The buffer which held
+    /// connection data is returned, and should be released to the `PortArena`
+    /// from which it was obtained.
+    unsafe fn disconnect(&self) -> PortBuffer;
 }
 
 /// Represents logical disconnection for an input port (note that the current
@@ -134,17 +163,18 @@ impl PortDisconnect for Arc<SyncUnsafeCell<PortInput>> {
     /// This implementation is not logically thread safe - there is no locking
     /// involved in the implementation, so this should only be called from a
     /// single thread.
-    unsafe fn disconnect(&self) {
+    unsafe fn disconnect(&self) -> PortBuffer {
         let input = unsafe { &mut (*self.get()) };
 
         match &input {
             Port::Connected(output) => {
                 let output = unsafe { &mut (*output.get()) };
 
-                match &output {
-                    Port::Connected(_) => {
-                        *output = Port::Disconnected;
+                match std::mem::take(output) {
+                    Port::Connected(buffer) => {
                         *input = Port::Disconnected;
+
+                        buffer
                     }
                     Port::Disconnected => panic!("output disconnected"),
                 }
@@ -186,7 +216,7 @@ impl PortInputGet for PortInputs {
 /// a module. It carries optional properties, such as name and description. It
 /// is not created directly in module definition code, but by using the
 /// associated methods on a module definition builder.
-#[derive(Builder, Debug)]
+#[derive(Builder, Clone, Debug, Deserialize, Serialize)]
 #[builder(derive(Debug), on(String, into))]
 pub struct PortInputDefinition {
     /// The name of the port
@@ -223,8 +253,8 @@ impl PortInputVectorGet for PortInputs {
             .get(port)
             .map(|input| match unsafe { &(*input.get()) } {
                 PortInput::Connected(output) => match unsafe { &(*output.get()) } {
-                    PortOutput::Connected(vectors) => {
-                        Port::Connected(unsafe { vectors.get_unchecked(usize::from(token.0 == 0)) })
+                    PortOutput::Connected(buffer) => {
+                        Port::Connected(unsafe { &*buffer.vector(usize::from(token.0 == 0)) })
                     }
                     PortOutput::Disconnected => Port::Disconnected,
                 },
@@ -256,14 +286,28 @@ impl PortInputs {
     }
 }
 
+impl PortInputs {
+    /// The number of input ports
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    /// Whether there are no input ports
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+}
+
 // -------------------------------------------------------------------------------------------------
 
 // Output
 
 /// `PortOuput` is a specialization of the generic Port type, where the data
-/// associated with the connected state is a pair of `Vector`s, used for writing
-/// in a striped pattern based on the current iteration.
-pub(crate) type PortOutput = Port<Box<[Vector; 2]>>;
+/// associated with the connected state is a buffer holding a pair of `Vector`s,
+/// used for writing in a striped pattern based on the current iteration.
+pub(crate) type PortOutput = Port<PortBuffer>;
 
 /// Represents the action of obtaining a `PortOutput` if one is available within
 /// the relevant container.
@@ -287,7 +331,7 @@ impl PortOutputGet for PortOutputs {
 /// on a module. It carries optional properties, such as name and description.
 /// It is not created directly in module definition code, but by using the
 /// associated methods on a module definition builder.
-#[derive(Builder, Debug)]
+#[derive(Builder, Clone, Debug, Deserialize, Serialize)]
 #[builder(derive(Debug), on(String, into))]
 pub struct PortOutputDefinition {
     /// The name of the port
@@ -339,11 +383,9 @@ impl PortOutputVectorGet for PortOutputs {
     fn vector(&mut self, port: usize, token: &ProcessToken) -> Option<Port<&mut Vector>> {
         self.outputs
             .get(port)
-            .map(|output| match unsafe { &mut (*output.get()) } {
-                PortOutput::Connected(vectors) => {
-                    let current = unsafe { vectors.get_unchecked_mut(token.0) };
-
-                    Port::Connected(current)
+            .map(|output| match unsafe { &(*output.get()) } {
+                PortOutput::Connected(buffer) => {
+                    Port::Connected(unsafe { &mut *buffer.vector(token.0) })
                 }
                 PortOutput::Disconnected => Port::Disconnected,
             })
@@ -356,14 +398,10 @@ impl PortOutputVectorGet for PortOutputs {
     ) -> Option<Port<(&mut Vector, &Vector)>> {
         self.outputs
             .get(port)
-            .map(|output| match unsafe { &mut (*output.get()) } {
-                PortOutput::Connected(vectors) => {
-                    let [current, previous] = unsafe {
-                        vectors.get_disjoint_unchecked_mut([token.0, usize::from(token.0 == 0)])
-                    };
-
-                    let current: &mut Vector = current;
-                    let previous: &Vector = previous;
+            .map(|output| match unsafe { &(*output.get()) } {
+                PortOutput::Connected(buffer) => {
+                    let current = unsafe { &mut *buffer.vector(token.0) };
+                    let previous = unsafe { &*buffer.vector(usize::from(token.0 == 0)) };
 
                     Port::Connected((current, previous))
                 }
@@ -394,3 +432,126 @@ impl PortOutputs {
         Self::new(output)
     }
 }
+
+impl PortOutputs {
+    /// The number of output ports
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.outputs.len()
+    }
+
+    /// Whether there are no output ports
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.outputs.is_empty()
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Buffer
+
+/// The number of buffers in each block allocated by a `PortArena` (each buffer
+/// is a pair of vectors, so a block is 256KiB at the default vector size).
+const PORT_ARENA_BLOCK: usize = 256;
+
+/// A `PortSlot` is the cache-aligned storage for a single buffer within a
+/// `PortArena`.
+#[derive(Debug, Default)]
+#[repr(C, align(64))]
+pub(crate) struct PortSlot([Vector; 2]);
+
+/// A `PortBuffer` is a pointer to a slot within a `PortArena`, held by a
+/// connected output port. The buffer is only valid while the arena from which
+/// it was obtained exists, and while it has not been released.
+#[derive(Debug)]
+pub(crate) struct PortBuffer(NonNull<PortSlot>);
+
+impl PortBuffer {
+    /// Gets a pointer to one of the pair of vectors (the given stripe, 0 or 1)
+    /// held by the buffer. No reference to the pair is created, so a reference
+    /// to one vector never overlaps a reference to the other, which may be held
+    /// concurrently by another thread.
+    ///
+    /// # Safety
+    ///
+    /// The stripe must be 0 or 1. The pointer is not tied to any borrow of the
+    /// arena - the caller must ensure that the striped access pattern
+    /// described in the module documentation is maintained when dereferencing
+    /// it.
+    unsafe fn vector(&self, stripe: usize) -> *mut Vector {
+        unsafe { (&raw mut (*self.0.as_ptr()).0).cast::<Vector>().add(stripe) }
+    }
+}
+
+unsafe impl Send for PortBuffer {}
+unsafe impl Sync for PortBuffer {}
+
+// -------------------------------------------------------------------------------------------------
+
+// Arena
+
+/// A `PortArena` owns the storage for the buffers of all connected output
+/// ports within a processor. Storage is allocated in contiguous blocks of
+/// cache-aligned slots, and released buffers are reused before any new block
+/// is allocated (blocks are never freed or moved, so buffers remain valid for
+/// the lifetime of the arena).
+#[derive(Debug)]
+pub(crate) struct PortArena {
+    blocks: Vec<Box<[PortSlot]>>,
+    free: Vec<PortBuffer>,
+}
+
+impl Default for PortArena {
+    fn default() -> Self {
+        let mut arena = Self {
+            blocks: Vec::new(),
+            free: Vec::new(),
+        };
+
+        arena.grow();
+        arena
+    }
+}
+
+impl PortArena {
+    /// Allocates a buffer from the arena, with both vectors zeroed. A new block
+    /// is allocated if no free buffers remain.
+    pub(crate) fn allocate(&mut self) -> PortBuffer {
+        if self.free.is_empty() {
+            self.grow();
+        }
+
+        let buffer = self.free.pop().expect("free buffer to exist");
+
+        unsafe {
+            buffer.0.write(PortSlot::default());
+        }
+
+        buffer
+    }
+
+    /// Releases a buffer back to the arena for reuse.
+    pub(crate) fn release(&mut self, buffer: PortBuffer) {
+        self.free.push(buffer);
+    }
+
+    fn grow(&mut self) {
+        let mut block = (0..PORT_ARENA_BLOCK)
+            .map(|_| PortSlot::default())
+            .collect::<Box<[_]>>();
+
+        // Free buffers are taken from the end, so are added in reverse order to
+        // allocate from the start of the block (keeping buffers allocated in
+        // sequence adjacent in memory).
+
+        self.free.extend(
+            block
+                .iter_mut()
+                .rev()
+                .map(|slot| PortBuffer(NonNull::from(slot))),
+        );
+
+        self.blocks.push(block);
+    }
+}
@@ -1,24 +1,76 @@
 use std::{
+    array,
     cell::SyncUnsafeCell,
     fmt::Debug,
+    panic::{
+        self,
+        AssertUnwindSafe,
+    },
+    ptr,
+    simd::{
+        Select as _,
+        cmp::SimdPartialEq as _,
+        num::SimdFloat as _,
+    },
+    sync::{
+        Arc,
+        atomic::{
+            AtomicBool,
+            Ordering,
+        },
+    },
+    thread::JoinHandle,
+    time::Duration,
 };
 
 use fancy_constructor::new;
 use indexmap::IndexMap;
-use open_modular_core::Vector;
+use open_modular_core::{
+    BUFFER_FRAMES,
+    SAMPLE_RATE,
+    Sample,
+    Vector,
+};
+use open_modular_utilities::{
+    sync::{
+        Barrier,
+        BarrierGroup,
+        BarrierGroupPanic,
+    },
+    thread::BuilderExt as _,
+};
+use serde_json::Value;
+use thread_priority::{
+    ThreadBuilder,
+    ThreadPriority,
+};
 use uuid::Uuid;
 
 use crate::{
     bus::BusReceiver,
+    denormal::DenormalGuard,
+    event::{
+        EventFailed,
+        EventInvalid,
+        EventInvalidOutput,
+        EventSender,
+    },
     module::{
         Module,
+        ModuleBypass,
         ModuleSource,
     },
     port::{
+        Port,
+        PortArena,
+        PortBuffer,
         PortConnect as _,
         PortDisconnect as _,
+        PortIndex,
         PortInputGet as _,
+        PortInputVectorGet as _,
         PortOutputGet as _,
+        PortOutputVectorGet as _,
     },
 };
 
@@ -30,13 +82,43 @@ use crate::{
 
 pub trait Process {
     fn process(&mut self, args: &ProcessArgs);
+
+    /// Called when the processor is reset (for example, when a transport is
+    /// reset). Modules should return any internal state (delay lines, phase,
+    /// etc.) to the initial state.
+    fn reset(&mut self) {}
+
+    /// Called when a port of the module has been connected.
+    fn on_connect(&mut self, _port: PortIndex) {}
+
+    /// Called when a port of the module has been disconnected (including when
+    /// the module at the other end of the connection is removed).
+    fn on_disconnect(&mut self, _port: PortIndex) {}
+
+    /// Called when the module is about to be removed from the processor, after
+    /// all of the ports of the module have been disconnected.
+    fn on_remove(&mut self) {}
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct ProcessArgs {
+    /// The output gain of the processor for each frame of the current
+    /// iteration. Modules which write output outside of the processor (such as
+    /// audio outputs) should apply this gain, which allows the output of a
+    /// processor to be faded (for example, when switching between patches).
+    pub gain: Vector,
     pub token: ProcessToken,
 }
 
+impl Default for ProcessArgs {
+    fn default() -> Self {
+        Self {
+            gain: Vector::splat(1.),
+            token: ProcessToken::default(),
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct ProcessToken(pub(crate) usize);
 
@@ -49,11 +131,93 @@ pub struct Processor<M>
 where
     M: Module,
 {
+    #[new(default)]
+    arena: PortArena,
     #[new(default)]
     args: ProcessArgs,
     #[new(default)]
-    modules: IndexMap<Uuid, SyncUnsafeCell<M>>,
+    connections: IndexMap<(Uuid, usize), (Uuid, usize)>,
+    #[new(default)]
+    debug: Option<ProcessorDebug>,
+    #[new(default)]
+    events: Option<EventSender>,
+    #[new(val = ProcessorFade::new(1.))]
+    gain: ProcessorFade,
+    #[new(default)]
+    isolate: bool,
+    #[new(default)]
+    modules: IndexMap<Uuid, SyncUnsafeCell<ProcessorInstance<M>>>,
     receiver: BusReceiver,
+    #[new(default)]
+    schedule: Vec<usize>,
+    #[new(default)]
+    silent: Vec<ProcessorSilent>,
+    #[new(default)]
+    workers: Option<ProcessorWorkers>,
+}
+
+impl<M> Processor<M>
+where
+    M: Module + Send,
+{
+    /// Creates a new processor which processes instances using the given
+    /// number of threads (including the calling thread, so a value of one or
+    /// less is equivalent to `Processor::new`). Additional threads are spawned
+    /// as worker threads, at realtime priority where the platform allows.
+    ///
+    /// As instances only read the output of other instances from the previous
+    /// iteration (see the `port` module), all instances within an iteration
+    /// are independent, and the result of processing is identical regardless
+    /// of the number of threads used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a worker thread cannot be spawned.
+    #[must_use]
+    pub fn with_threads(receiver: BusReceiver, threads: usize) -> Self {
+        let mut processor = Self::new(receiver);
+
+        processor.workers = (threads > 1).then(|| ProcessorWorkers::spawn::<M>(threads));
+        processor
+    }
+}
+
+impl<M> Processor<M>
+where
+    M: Module,
+{
+    /// Sets the sender to which events raised by the processor are sent. If no
+    /// sender is set, events are discarded.
+    #[must_use]
+    pub fn with_events(mut self, events: EventSender) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Enables debug mode, in which every connected output of every processed
+    /// instance is scanned for invalid values (NaN, infinite, or denormal)
+    /// after each iteration. See `ProcessorDebug` for details.
+    #[must_use]
+    pub fn with_debug(mut self, debug: ProcessorDebug) -> Self {
+        self.debug = Some(debug);
+        self
+    }
+
+    /// Enables isolation ("performance safety") mode, in which a panic within
+    /// the `process` function of an instance is caught rather than being
+    /// fatal. The failed instance is quarantined - it is no longer processed,
+    /// all outputs are silenced, and a failure event is sent - while the rest
+    /// of the processor continues as normal. A quarantined instance remains so
+    /// until it is removed.
+    ///
+    /// Isolation relaxes the expectation that any engine error is fatal (see
+    /// ADR 0006), and is intended for performance use, where continuity
+    /// matters more than correctness of an individual instance.
+    #[must_use]
+    pub fn with_isolation(mut self) -> Self {
+        self.isolate = true;
+        self
+    }
 }
 
 impl<M> Processor<M>
@@ -61,13 +225,264 @@ where
     M: Debug + Module,
 {
     pub fn add(&mut self, instance: Uuid, module: M) {
-        let module = SyncUnsafeCell::new(module);
+        let module = SyncUnsafeCell::new(ProcessorInstance::from_module(module));
 
         self.modules.insert(instance, module);
+        self.silent.push(ProcessorSilent::default());
+        self.graph();
     }
 
+    /// Removes an instance, first disconnecting any connections to or from the
+    /// ports of the instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance cannot be found.
     pub fn remove(&mut self, instance: &Uuid) {
-        self.modules.swap_remove(instance);
+        let inputs = self
+            .connections
+            .iter()
+            .filter(|((input_instance, _), (output_instance, _))| {
+                input_instance == instance || output_instance == instance
+            })
+            .map(|(input, _)| *input)
+            .collect::<Vec<_>>();
+
+        for (input_instance, input_port) in inputs {
+            unsafe {
+                self.disconnect(input_instance, input_port);
+            }
+        }
+
+        let (index, _, mut removed) = self
+            .modules
+            .swap_remove_full(instance)
+            .expect("instance to exist");
+
+        self.silent.swap_remove(index);
+
+        removed.get_mut().module.on_remove();
+
+        self.graph();
+    }
+
+    /// Replaces the module of an instance with a new module, keeping the
+    /// connections of the instance where possible. Each connected port of the
+    /// existing module is rewired to the port of the new module with the same
+    /// name or, failing that, the port at the same index where both ports are
+    /// unnamed (or have the same name). Connections to ports with no compatible
+    /// port on the new module are dropped. The bypass and mute state of the
+    /// instance is retained.
+    ///
+    /// Rewired connections keep their existing buffers, so the replacement
+    /// happens within a single iteration without silencing any connection.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance cannot be found.
+    pub fn replace(&mut self, instance: &Uuid, module: M) {
+        let previous = self
+            .modules
+            .get_mut(instance)
+            .expect("instance to exist")
+            .get_mut()
+            .module
+            .definition();
+
+        let definition = module.definition();
+
+        let inputs = rewire(
+            previous.inputs.iter().map(|port| port.name.as_deref()),
+            definition.inputs.iter().map(|port| port.name.as_deref()),
+        );
+
+        let outputs = rewire(
+            previous.outputs.iter().map(|port| port.name.as_deref()),
+            definition.outputs.iter().map(|port| port.name.as_deref()),
+        );
+
+        let connections = self
+            .connections
+            .iter()
+            .filter(|((input_instance, _), (output_instance, _))| {
+                input_instance == instance || output_instance == instance
+            })
+            .map(|(input, output)| (*input, *output))
+            .collect::<Vec<_>>();
+
+        let mut rewired = Vec::with_capacity(connections.len());
+
+        for ((input_instance, input_port), (output_instance, output_port)) in connections {
+            let buffer = unsafe { self.detach(input_instance, input_port) };
+
+            let input_port = if input_instance == *instance {
+                inputs.get(input_port).copied().flatten()
+            } else {
+                Some(input_port)
+            };
+
+            let output_port = if output_instance == *instance {
+                outputs.get(output_port).copied().flatten()
+            } else {
+                Some(output_port)
+            };
+
+            match (input_port, output_port) {
+                (Some(input_port), Some(output_port)) => rewired.push((
+                    (input_instance, input_port),
+                    (output_instance, output_port),
+                    buffer,
+                )),
+                _ => self.arena.release(buffer),
+            }
+        }
+
+        let (index, _, current) = self
+            .modules
+            .get_full_mut(instance)
+            .expect("instance to exist");
+
+        let current = current.get_mut();
+
+        self.silent[index] = ProcessorSilent::default();
+
+        let mut replacement = ProcessorInstance::from_module(module);
+
+        replacement.bypassed = current.bypassed;
+        replacement.muted = current.muted;
+        replacement.dry = current.dry;
+        replacement.wet = current.wet;
+        replacement.update();
+
+        std::mem::replace(current, replacement).module.on_remove();
+
+        for ((input_instance, input_port), (output_instance, output_port), buffer) in rewired {
+            unsafe {
+                self.attach(
+                    input_instance,
+                    input_port,
+                    output_instance,
+                    output_port,
+                    buffer,
+                );
+            }
+        }
+
+        self.graph();
+    }
+
+    /// Resets all instances.
+    pub fn reset(&mut self) {
+        self.modules
+            .values_mut()
+            .for_each(|instance| instance.get_mut().module.reset());
+    }
+}
+
+impl<M> Processor<M>
+where
+    M: Module,
+{
+    /// Sets whether an instance is bypassed. A bypassed instance passes the
+    /// input declared as the bypass input in the module definition straight
+    /// through to the declared bypass output (any other outputs are silent). An
+    /// instance of a module with no declared bypass is silent when bypassed.
+    /// Changes are crossfaded over a short period to avoid discontinuities.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance cannot be found.
+    pub fn bypass(&mut self, instance: &Uuid, bypass: bool) {
+        let instance = self
+            .modules
+            .get_mut(instance)
+            .expect("instance to exist")
+            .get_mut();
+
+        instance.bypassed = bypass;
+        instance.update();
+    }
+
+    /// Sets whether an instance is muted. A muted instance continues to
+    /// process, but writes silence to all outputs (muting takes precedence over
+    /// bypassing). Changes are crossfaded over a short period to avoid
+    /// discontinuities.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance cannot be found.
+    pub fn mute(&mut self, instance: &Uuid, mute: bool) {
+        let instance = self
+            .modules
+            .get_mut(instance)
+            .expect("instance to exist")
+            .get_mut();
+
+        instance.muted = mute;
+        instance.update();
+    }
+}
+
+impl<M> Processor<M>
+where
+    M: Module,
+{
+    /// Fades the output gain of the processor (see `ProcessArgs::gain`) from
+    /// the current gain to the given gain over the given duration (a zero
+    /// duration sets the gain immediately).
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    pub fn fade(&mut self, gain: Sample, duration: Duration) {
+        let frames = (duration.as_secs_f64() * SAMPLE_RATE as f64).round() as usize;
+
+        self.gain.target = gain;
+        self.gain.frames = frames;
+
+        if frames == 0 {
+            self.gain.value = gain;
+        }
+    }
+
+    /// Whether the output gain applied during the most recent iteration was
+    /// zero for every frame (and so any output written outside of the processor
+    /// is now silent).
+    #[must_use]
+    pub fn is_faded_out(&self) -> bool {
+        self.args.gain.simd_eq(Vector::splat(0.)).all()
+    }
+}
+
+impl<M> Processor<M>
+where
+    M: Module,
+{
+    /// Gets a snapshot of the state of every instance which has state, paired
+    /// with the id of the instance.
+    pub fn snapshot(&self) -> Vec<(Uuid, Value)> {
+        self.modules
+            .iter()
+            .filter_map(|(id, instance)| {
+                unsafe { (*instance.get()).module.snapshot() }.map(|snapshot| (*id, snapshot))
+            })
+            .collect()
+    }
+
+    /// Restores the state of an instance from a snapshot.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance cannot be found, or if the snapshot is not a
+    /// valid state for the module of the instance.
+    pub fn restore(&mut self, instance: &Uuid, snapshot: Value) {
+        self.modules
+            .get_mut(instance)
+            .expect("instance to exist")
+            .get_mut()
+            .module
+            .restore_snapshot(snapshot);
     }
 }
 
@@ -92,49 +507,212 @@ where
         input_port: usize,
         output_instance: Uuid,
         output_port: usize,
+    ) {
+        let buffer = self.arena.allocate();
+
+        unsafe {
+            self.attach(
+                input_instance,
+                input_port,
+                output_instance,
+                output_port,
+                buffer,
+            );
+        }
+
+        self.graph();
+    }
+
+    /// Disconnects a currently connected input port (and the output port to
+    /// which it is connected).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the input port cannot be found (either the instance or the
+    /// port index). Panics if the input port is not currently connected.
+    ///
+    /// # Safety
+    ///
+    /// .
+    pub unsafe fn disconnect(&mut self, input_instance: Uuid, input_port: usize) {
+        let buffer = unsafe { self.detach(input_instance, input_port) };
+
+        self.arena.release(buffer);
+        self.graph();
+    }
+
+    /// Connects two ports using the given buffer, without updating the graph.
+    unsafe fn attach(
+        &mut self,
+        input_instance: Uuid,
+        input_port: usize,
+        output_instance: Uuid,
+        output_port: usize,
+        buffer: PortBuffer,
     ) {
         let outputs = self
             .modules
             .get(&output_instance)
-            .map(|instance| unsafe { (*instance.get()).as_mut() })
+            .map(|instance| unsafe { (*instance.get()).module.as_mut() })
             .expect("output instance to exist");
 
         let inputs = self
             .modules
             .get(&input_instance)
-            .map(|instance| unsafe { (*instance.get()).as_ref() })
+            .map(|instance| unsafe { (*instance.get()).module.as_ref() })
             .expect("input instance to exist");
 
         let output = outputs.port(output_port).expect("output port to exist");
         let input = inputs.port(input_port).expect("input port to exist");
 
         unsafe {
-            output.connect(input);
+            output.connect(input, buffer);
         }
+
+        self.connections
+            .insert((input_instance, input_port), (output_instance, output_port));
+
+        self.notify(&output_instance, |module| {
+            module.on_connect(PortIndex::Output(output_port));
+        });
+
+        self.notify(&input_instance, |module| {
+            module.on_connect(PortIndex::Input(input_port));
+        });
     }
 
-    /// .
-    ///
-    /// # Panics
-    ///
-    /// Panics when...
-    ///
-    /// # Safety
-    ///
-    /// .
-    pub unsafe fn disconnect(&mut self, input_instance: Uuid, input_port: usize) {
+    /// Disconnects an input port, returning the buffer of the connection
+    /// without releasing it or updating the graph.
+    unsafe fn detach(&mut self, input_instance: Uuid, input_port: usize) -> PortBuffer {
         let inputs = self
             .modules
             .get(&input_instance)
-            .map(|instance| unsafe { (*instance.get()).as_ref() })
+            .map(|instance| unsafe { (*instance.get()).module.as_ref() })
             .expect("input instance to exist");
 
         let input = inputs.port(input_port).expect("input port to exist");
 
-        unsafe {
-            input.disconnect();
+        let buffer = unsafe { input.disconnect() };
+
+        let (output_instance, output_port) = self
+            .connections
+            .swap_remove(&(input_instance, input_port))
+            .expect("connection to exist");
+
+        self.notify(&output_instance, |module| {
+            module.on_disconnect(PortIndex::Output(output_port));
+        });
+
+        self.notify(&input_instance, |module| {
+            module.on_disconnect(PortIndex::Input(input_port));
+        });
+
+        buffer
+    }
+
+    /// Updates the graph state of every instance after a change to the
+    /// instances or connections of the processor. An instance is awake if it is
+    /// a sink, or if any of the outputs of the instance are connected to an
+    /// awake instance. Instances which are not awake cannot affect any sink,
+    /// and so are not scheduled for processing (their outputs are silenced when
+    /// they go to sleep). The upstream instances of each instance (the
+    /// instances connected to the inputs of the instance) are also
+    /// recorded, to allow instances to sleep when all inputs are silent.
+    fn graph(&mut self) {
+        let mut upstream = vec![Vec::new(); self.modules.len()];
+
+        for ((input_instance, _), (output_instance, _)) in &self.connections {
+            let input = self.modules.get_index_of(input_instance);
+            let output = self.modules.get_index_of(output_instance);
+
+            if let (Some(input), Some(output)) = (input, output) {
+                upstream[input].push(output);
+            }
+        }
+
+        let mut awake = self
+            .modules
+            .values_mut()
+            .map(|instance| instance.get_mut().sink)
+            .collect::<Vec<_>>();
+
+        let mut pending = (0..awake.len()).filter(|i| awake[*i]).collect::<Vec<_>>();
+
+        while let Some(i) = pending.pop() {
+            for output in &upstream[i] {
+                if !awake[*output] {
+                    awake[*output] = true;
+                    pending.push(*output);
+                }
+            }
+        }
+
+        self.schedule = (0..awake.len()).filter(|i| awake[*i]).collect();
+
+        for (((instance, silent), upstream), awake) in self
+            .modules
+            .values_mut()
+            .zip(&self.silent)
+            .zip(upstream)
+            .zip(awake)
+        {
+            let instance = instance.get_mut();
+
+            if instance.awake && !awake {
+                instance.silence(&ProcessToken(0));
+                instance.silence(&ProcessToken(1));
+                silent.set_all(true);
+            }
+
+            instance.awake = awake;
+            instance.upstream = upstream;
+        }
+    }
+
+    fn notify<F>(&mut self, instance: &Uuid, f: F)
+    where
+        F: FnOnce(&mut M),
+    {
+        if let Some(instance) = self.modules.get_mut(instance) {
+            f(&mut instance.get_mut().module);
+        }
+    }
+}
+
+/// Maps each of a set of previous ports (given by name) to a compatible port
+/// of a set of new ports, matching first by name, then by index (where the new
+/// port at the same index has not already been matched by name, and has the
+/// same name as the previous port, or both ports are unnamed). A port with a
+/// different name at the same index is not compatible.
+fn rewire<'a>(
+    previous: impl Iterator<Item = Option<&'a str>>,
+    new: impl Iterator<Item = Option<&'a str>>,
+) -> Vec<Option<usize>> {
+    let previous = previous.collect::<Vec<_>>();
+    let new = new.collect::<Vec<_>>();
+    let mut claimed = vec![false; new.len()];
+
+    let mut ports = previous
+        .iter()
+        .map(|name| {
+            let port = name.and_then(|name| new.iter().position(|new| *new == Some(name)));
+
+            if let Some(port) = port {
+                claimed[port] = true;
+            }
+
+            port
+        })
+        .collect::<Vec<_>>();
+
+    for (i, port) in ports.iter_mut().enumerate() {
+        if port.is_none() && claimed.get(i) == Some(&false) && new[i] == previous[i] {
+            claimed[i] = true;
+            *port = Some(i);
         }
     }
+
+    ports
 }
 
 impl<M> Processor<M>
@@ -147,13 +725,576 @@ where
         M: Debug + ModuleSource<Context = C>,
     {
         self.args.token.0 = (iteration % 2) as usize;
+        self.args.gain = self.gain.advance();
 
         if let Some(protocol) = self.receiver.receive() {
             protocol.apply(context, self);
         }
 
-        self.modules.values().for_each(|module| unsafe {
-            (*module.get()).process(&self.args);
+        let _guard = DenormalGuard::new();
+
+        match &mut self.workers {
+            Some(workers) => {
+                workers.process(
+                    &self.modules,
+                    &self.silent,
+                    &self.schedule,
+                    &self.args,
+                    self.isolate,
+                );
+            }
+            None => process(
+                &self.modules,
+                &self.silent,
+                &self.schedule,
+                &self.args,
+                self.isolate,
+            ),
+        }
+
+        if self.isolate {
+            self.report();
+        }
+
+        if let Some(debug) = &mut self.debug {
+            debug.scan(
+                &self.modules,
+                &self.schedule,
+                &self.args.token,
+                self.events.as_mut(),
+            );
+        }
+    }
+
+    /// Reports any newly failed instances (only relevant in isolation mode).
+    fn report(&mut self) {
+        for i in &self.schedule {
+            let (id, instance) = self.modules.get_index_mut(*i).expect("instance to exist");
+            let instance = instance.get_mut();
+
+            if instance.failed && !instance.reported {
+                instance.reported = true;
+
+                if let Some(events) = &mut self.events {
+                    events.send(EventFailed::new(*id).into());
+                }
+            }
+        }
+    }
+}
+
+/// Processes the scheduled instances (given as indices into the instances).
+/// This may be called concurrently for disjoint schedules within the same
+/// iteration, as instances only read the output of other instances written in
+/// the previous iteration. If `isolate` is set, a panic while processing an
+/// instance is caught, and the instance is marked as failed.
+///
+/// Each call only accesses the instances within the given schedule - whether
+/// the upstream instances of an instance were silent in the previous iteration
+/// is read from the separate silent flags (see `ProcessorSilent`), never from
+/// the upstream instances themselves, which may be being processed
+/// concurrently.
+fn process<M>(
+    modules: &IndexMap<Uuid, SyncUnsafeCell<ProcessorInstance<M>>>,
+    silent: &[ProcessorSilent],
+    schedule: &[usize],
+    args: &ProcessArgs,
+    isolate: bool,
+) where
+    M: Module,
+{
+    let token = &args.token;
+    let previous = &ProcessToken(token.0 ^ 1);
+
+    for i in schedule {
+        let instance = unsafe { &mut *modules[*i].get() };
+
+        let sleeping = instance.sleep
+            && instance
+                .upstream
+                .iter()
+                .all(|upstream| silent[*upstream].get(previous));
+
+        let silent = &silent[*i];
+
+        if instance.failed {
+            if !silent.get(token) {
+                instance.silence(token);
+                silent.set(token, true);
+            }
+
+            continue;
+        }
+
+        if sleeping {
+            if !silent.get(token) {
+                instance.silence(token);
+            }
+        } else if isolate {
+            let module = &mut instance.module;
+
+            if panic::catch_unwind(AssertUnwindSafe(|| module.process(args))).is_err() {
+                instance.failed = true;
+                instance.silence(token);
+                silent.set(token, true);
+
+                continue;
+            }
+        } else {
+            instance.module.process(args);
+        }
+
+        instance.mix(token);
+        silent.set(token, sleeping || instance.is_silent(token));
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Debug
+
+/// `ProcessorDebug` configures the debug mode of a processor. In debug mode,
+/// each connected output of each processed instance is scanned for NaN,
+/// infinite, and denormal values after every iteration, and an event is sent
+/// (if the processor has an event sender) for each output containing invalid
+/// values (identifying the instance and the port). If `clamp` is set, invalid
+/// values are also replaced with zero before they can be read by any connected
+/// input.
+///
+/// Scanning happens on the calling thread once all instances have been
+/// processed, and has a cost proportional to the number of connected outputs,
+/// so debug mode is not intended for general use.
+#[derive(new, Debug)]
+pub struct ProcessorDebug {
+    clamp: bool,
+}
+
+impl ProcessorDebug {
+    fn scan<M>(
+        &self,
+        modules: &IndexMap<Uuid, SyncUnsafeCell<ProcessorInstance<M>>>,
+        schedule: &[usize],
+        token: &ProcessToken,
+        mut events: Option<&mut EventSender>,
+    ) where
+        M: Module,
+    {
+        let zero = Vector::splat(0.);
+
+        for i in schedule {
+            let (id, instance) = modules.get_index(*i).expect("instance to exist");
+            let outputs = unsafe { (*instance.get()).module.as_mut() };
+
+            for port in 0..outputs.len() {
+                if let Some(Port::Connected(output)) = outputs.vector(port, token) {
+                    let nan = output.is_nan();
+                    let infinite = output.is_infinite();
+                    let denormal = output.is_subnormal();
+
+                    let invalid = if nan.any() {
+                        EventInvalid::NaN
+                    } else if infinite.any() {
+                        EventInvalid::Infinite
+                    } else if denormal.any() {
+                        EventInvalid::Denormal
+                    } else {
+                        continue;
+                    };
+
+                    if let Some(events) = &mut events {
+                        events.send(EventInvalidOutput::new(*id, port, invalid).into());
+                    }
+
+                    if self.clamp {
+                        *output = (nan | infinite | denormal).select(zero, *output);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Workers
+
+/// `ProcessorWorkers` is a pool of worker threads, each of which processes a
+/// contiguous partition of the schedule on each iteration (the calling thread
+/// processes the first partition). Workers are synchronized with the calling
+/// thread using barriers at the start and end of each iteration.
+#[derive(new, Debug)]
+struct ProcessorWorkers {
+    end: Barrier,
+    handles: Vec<JoinHandle<()>>,
+    shared: Arc<ProcessorWorkersShared>,
+    start: Barrier,
+}
+
+impl ProcessorWorkers {
+    fn spawn<M>(threads: usize) -> Self
+    where
+        M: Module + Send,
+    {
+        let end = BarrierGroup::new(BarrierGroupPanic::Poison);
+        let start = BarrierGroup::new(BarrierGroupPanic::Poison);
+        let shared = Arc::new(ProcessorWorkersShared::new(threads));
+
+        // NOTE: All barriers (including those of the calling thread) are
+        // created before any worker thread is spawned, as a worker could
+        // otherwise pass a barrier before the full set of barriers is active,
+        // and find no iteration to process (exiting, and leaving a partition
+        // of the schedule unprocessed).
+
+        let barriers = (1..threads)
+            .map(|partition| (partition, end.barrier(), start.barrier()))
+            .collect::<Vec<_>>();
+
+        let (end_caller, start_caller) = (end.barrier(), start.barrier());
+
+        let handles = barriers
+            .into_iter()
+            .map(|(partition, mut end, mut start)| {
+                let shared = Arc::clone(&shared);
+
+                ThreadBuilder::default()
+                    .named(format!("processor/{partition}"))
+                    .priority(ThreadPriority::Max)
+                    .spawn_careless(move || {
+                        loop {
+                            start.wait();
+
+                            let _guard = DenormalGuard::new();
+
+                            match unsafe { &*shared.iteration.get() } {
+                                Some(iteration) => unsafe {
+                                    iteration.process::<M>(partition, shared.threads);
+                                },
+                                None => break,
+                            }
+
+                            end.wait();
+                        }
+                    })
+                    .expect("worker thread to spawn without error")
+            })
+            .collect();
+
+        Self::new(end_caller, handles, shared, start_caller)
+    }
+}
+
+impl ProcessorWorkers {
+    fn process<M>(
+        &mut self,
+        modules: &IndexMap<Uuid, SyncUnsafeCell<ProcessorInstance<M>>>,
+        silent: &[ProcessorSilent],
+        schedule: &[usize],
+        args: &ProcessArgs,
+        isolate: bool,
+    ) where
+        M: Module,
+    {
+        let iteration = ProcessorIteration::new(
+            ptr::from_ref(args),
+            isolate,
+            ptr::from_ref(modules).cast(),
+            ptr::from_ref(schedule),
+            ptr::from_ref(silent),
+        );
+
+        unsafe {
+            *self.shared.iteration.get() = Some(iteration);
+        }
+
+        self.start.wait();
+
+        process(
+            modules,
+            silent,
+            partition(schedule, 0, self.shared.threads),
+            args,
+            isolate,
+        );
+
+        self.end.wait();
+    }
+}
+
+impl Drop for ProcessorWorkers {
+    fn drop(&mut self) {
+        unsafe {
+            *self.shared.iteration.get() = None;
+        }
+
+        self.start.wait();
+
+        for handle in self.handles.drain(..) {
+            drop(handle.join());
+        }
+    }
+}
+
+/// Gets the partition of the schedule to be processed by the given thread,
+/// where the schedule is divided into (at most) the given number of contiguous
+/// partitions.
+fn partition(schedule: &[usize], partition: usize, threads: usize) -> &[usize] {
+    schedule
+        .chunks(schedule.len().div_ceil(threads).max(1))
+        .nth(partition)
+        .unwrap_or_default()
+}
+
+#[derive(new, Debug)]
+struct ProcessorWorkersShared {
+    #[new(default)]
+    iteration: SyncUnsafeCell<Option<ProcessorIteration>>,
+    threads: usize,
+}
+
+/// A `ProcessorIteration` holds pointers to the state required to process a
+/// single iteration, valid between the start and end barriers of that
+/// iteration (the instances are held as an untyped pointer, as the workers are
+/// not generic over the module type).
+#[derive(new, Debug)]
+struct ProcessorIteration {
+    args: *const ProcessArgs,
+    isolate: bool,
+    modules: *const (),
+    schedule: *const [usize],
+    silent: *const [ProcessorSilent],
+}
+
+impl ProcessorIteration {
+    unsafe fn process<M>(&self, partition: usize, threads: usize)
+    where
+        M: Module,
+    {
+        let args = unsafe { &*self.args };
+        let modules = unsafe {
+            &*self
+                .modules
+                .cast::<IndexMap<Uuid, SyncUnsafeCell<ProcessorInstance<M>>>>()
+        };
+        let schedule = unsafe { &*self.schedule };
+        let silent = unsafe { &*self.silent };
+
+        process(
+            modules,
+            silent,
+            self::partition(schedule, partition, threads),
+            args,
+            self.isolate,
+        );
+    }
+}
+
+unsafe impl Send for ProcessorIteration {}
+unsafe impl Sync for ProcessorIteration {}
+
+// -------------------------------------------------------------------------------------------------
+
+// Instance
+
+#[allow(clippy::struct_excessive_bools)]
+#[derive(new, Debug)]
+struct ProcessorInstance<M>
+where
+    M: Module,
+{
+    #[new(val = true)]
+    awake: bool,
+    bypass: Option<ModuleBypass>,
+    #[new(default)]
+    bypassed: bool,
+    #[new(val = ProcessorFade::new(0.))]
+    dry: ProcessorFade,
+    #[new(default)]
+    failed: bool,
+    module: M,
+    #[new(default)]
+    muted: bool,
+    #[new(default)]
+    reported: bool,
+    sink: bool,
+    sleep: bool,
+    #[new(default)]
+    upstream: Vec<usize>,
+    #[new(val = ProcessorFade::new(1.))]
+    wet: ProcessorFade,
+}
+
+impl<M> ProcessorInstance<M>
+where
+    M: Module,
+{
+    fn from_module(module: M) -> Self {
+        let definition = module.definition();
+        let sink = definition.sink || definition.outputs.is_empty();
+
+        Self::new(definition.bypass, module, sink, definition.sleep)
+    }
+}
+
+impl<M> ProcessorInstance<M>
+where
+    M: Module,
+{
+    /// Whether all connected outputs of the module are silent for the given
+    /// token (trivially true if no outputs are connected).
+    fn is_silent(&mut self, token: &ProcessToken) -> bool {
+        let outputs = self.module.as_mut();
+        let zero = Vector::splat(0.);
+
+        (0..outputs.len()).all(|port| match outputs.vector(port, token) {
+            Some(Port::Connected(output)) => output.simd_eq(zero).all(),
+            _ => true,
+        })
+    }
+
+    /// Silences all connected outputs of the module for the given token.
+    fn silence(&mut self, token: &ProcessToken) {
+        let outputs = self.module.as_mut();
+
+        for port in 0..outputs.len() {
+            if let Some(Port::Connected(output)) = outputs.vector(port, token) {
+                *output = Vector::splat(0.);
+            }
+        }
+    }
+}
+
+impl<M> ProcessorInstance<M>
+where
+    M: Module,
+{
+    /// Mixes the outputs of the module (as written by the module during the
+    /// current iteration) with the bypass input, based on the current mute and
+    /// bypass settings. The common case (neither muted nor bypassed, and not
+    /// currently fading) returns immediately.
+    fn mix(&mut self, token: &ProcessToken) {
+        if self.wet.is_settled_at(1.) && self.dry.is_settled_at(0.) {
+            return;
+        }
+
+        let wet = self.wet.advance();
+        let dry = self.dry.advance();
+
+        let bypass = self.bypass.as_ref().and_then(|bypass| {
+            match self.module.as_ref().vector(bypass.input, token) {
+                Some(Port::Connected(input)) => Some((bypass.output, *input * dry)),
+                _ => None,
+            }
         });
+
+        let outputs = self.module.as_mut();
+
+        for port in 0..outputs.len() {
+            if let Some(Port::Connected(output)) = outputs.vector(port, token) {
+                *output *= wet;
+
+                if let Some((_, input)) = bypass.filter(|(bypass, _)| *bypass == port) {
+                    *output += input;
+                }
+            }
+        }
+    }
+
+    /// Updates the fade targets for the instance, based on the current mute and
+    /// bypass settings.
+    fn update(&mut self) {
+        let (wet, dry) = match (self.muted, self.bypassed, &self.bypass) {
+            (true, ..) | (false, true, None) => (0., 0.),
+            (false, true, Some(_)) => (0., 1.),
+            (false, false, _) => (1., 0.),
+        };
+
+        self.wet.target = wet;
+        self.dry.target = dry;
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Silent
+
+/// `ProcessorSilent` records whether all connected outputs of an instance were
+/// silent for each token. The flags are held by the processor (indexed in
+/// parallel with the instances) rather than within each instance, as an
+/// instance reads the flags of its upstream instances for the previous token
+/// while those instances may be being processed by another worker - reading
+/// them through the upstream instance would alias a mutable borrow. Relaxed
+/// ordering is sufficient, as the barriers between iterations order the writes
+/// of one iteration before the reads of the next.
+#[derive(Debug, Default)]
+struct ProcessorSilent([AtomicBool; 2]);
+
+impl ProcessorSilent {
+    fn get(&self, token: &ProcessToken) -> bool {
+        self.0[token.0].load(Ordering::Relaxed)
+    }
+
+    fn set(&self, token: &ProcessToken, silent: bool) {
+        self.0[token.0].store(silent, Ordering::Relaxed);
+    }
+
+    fn set_all(&self, silent: bool) {
+        for flag in &self.0 {
+            flag.store(silent, Ordering::Relaxed);
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Fade
+
+/// The default number of frames over which a change in mute or bypass state
+/// is faded (approximately 5ms).
+static FADE_FRAMES: usize = SAMPLE_RATE / 200;
+
+/// A `ProcessorFade` is a linear gain ramp from a current value to a target
+/// value over a number of frames (for a full change from zero to one),
+/// advanced a block (vector) at a time.
+#[derive(Clone, Copy, Debug)]
+struct ProcessorFade {
+    frames: usize,
+    target: Sample,
+    value: Sample,
+}
+
+impl ProcessorFade {
+    fn new(value: Sample) -> Self {
+        Self {
+            frames: FADE_FRAMES,
+            target: value,
+            value,
+        }
+    }
+}
+
+impl ProcessorFade {
+    #[allow(clippy::float_cmp)]
+    fn is_settled_at(&self, value: Sample) -> bool {
+        self.value == value && self.target == value
+    }
+
+    /// Advances the fade by one vector, returning the per-frame gain values for
+    /// that vector.
+    #[allow(clippy::cast_precision_loss, clippy::float_cmp)]
+    fn advance(&mut self) -> Vector {
+        if self.value == self.target {
+            return Vector::splat(self.value);
+        }
+
+        let step = (self.target - self.value).signum() / self.frames.max(1) as Sample;
+        let ramp = Vector::from_array(array::from_fn(|i| (i + 1) as Sample));
+        let gain = (ramp * Vector::splat(step) + Vector::splat(self.value)).simd_clamp(
+            Vector::splat(self.value.min(self.target)),
+            Vector::splat(self.value.max(self.target)),
+        );
+
+        self.value = gain[BUFFER_FRAMES - 1];
+
+        gain
     }
 }
@@ -1,11 +1,14 @@
 use std::fmt::Debug;
 
 use fancy_constructor::new;
+use open_modular_utilities::sync;
+use serde_json::Value;
 use uuid::Uuid;
 
 use crate::{
     module::{
         Module,
+        ModuleConfig,
         ModuleSource,
     },
     processor::Processor,
@@ -15,10 +18,22 @@ use crate::{
 // Protocol
 // =================================================================================================
 
-#[derive(Clone, Debug)]
+/// A `Protocol` is a single instruction to the engine. Protocols are not
+/// `Clone`, as a snapshot carries the sending half of a single-use reply
+/// channel (see `ProtocolSnapshot`) - every other protocol is `Clone`, and can
+/// be cloned before conversion where a protocol is to be sent more than once.
+#[derive(Debug)]
 pub enum Protocol {
     Add(ProtocolAdd),
+    Bypass(ProtocolBypass),
     Connect(ProtocolConnect),
+    Disconnect(ProtocolDisconnect),
+    Mute(ProtocolMute),
+    Remove(ProtocolRemove),
+    Replace(ProtocolReplace),
+    Reset(ProtocolReset),
+    Restore(ProtocolRestore),
+    Snapshot(ProtocolSnapshot),
 }
 
 impl Protocol {
@@ -29,7 +44,15 @@ impl Protocol {
     {
         match self {
             Self::Add(add) => add.apply(context.clone(), processor),
+            Self::Bypass(bypass) => bypass.apply(processor),
             Self::Connect(connect) => connect.apply(processor),
+            Self::Disconnect(disconnect) => disconnect.apply(processor),
+            Self::Mute(mute) => mute.apply(processor),
+            Self::Remove(remove) => remove.apply(processor),
+            Self::Replace(replace) => replace.apply(context.clone(), processor),
+            Self::Reset(reset) => reset.apply(processor),
+            Self::Restore(restore) => restore.apply(processor),
+            Self::Snapshot(snapshot) => snapshot.apply(processor),
         }
     }
 }
@@ -40,28 +63,108 @@ impl From<ProtocolAdd> for Protocol {
     }
 }
 
+impl From<ProtocolBypass> for Protocol {
+    fn from(bypass: ProtocolBypass) -> Self {
+        Self::Bypass(bypass)
+    }
+}
+
 impl From<ProtocolConnect> for Protocol {
     fn from(connect: ProtocolConnect) -> Self {
         Self::Connect(connect)
     }
 }
 
+impl From<ProtocolDisconnect> for Protocol {
+    fn from(disconnect: ProtocolDisconnect) -> Self {
+        Self::Disconnect(disconnect)
+    }
+}
+
+impl From<ProtocolMute> for Protocol {
+    fn from(mute: ProtocolMute) -> Self {
+        Self::Mute(mute)
+    }
+}
+
+impl From<ProtocolRemove> for Protocol {
+    fn from(remove: ProtocolRemove) -> Self {
+        Self::Remove(remove)
+    }
+}
+
+impl From<ProtocolReplace> for Protocol {
+    fn from(replace: ProtocolReplace) -> Self {
+        Self::Replace(replace)
+    }
+}
+
+impl From<ProtocolReset> for Protocol {
+    fn from(reset: ProtocolReset) -> Self {
+        Self::Reset(reset)
+    }
+}
+
+impl From<ProtocolRestore> for Protocol {
+    fn from(restore: ProtocolRestore) -> Self {
+        Self::Restore(restore)
+    }
+}
+
+impl From<ProtocolSnapshot> for Protocol {
+    fn from(snapshot: ProtocolSnapshot) -> Self {
+        Self::Snapshot(snapshot)
+    }
+}
+
 // Add
 
 #[derive(new, Clone, Debug)]
 pub struct ProtocolAdd {
+    #[new(into)]
     instance: Uuid,
     module: Uuid,
+    #[new(default)]
+    config: Option<ModuleConfig>,
 }
 
 impl ProtocolAdd {
+    /// Sets the instantiation-time configuration of the new instance, which
+    /// otherwise has the default configuration of the module. The
+    /// configuration is deserialized before sending (see `ModuleConfig`).
+    #[must_use]
+    pub fn with_config(mut self, config: ModuleConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Applies the add, which is ignored if the module is not found (or the
+    /// configuration is not a configuration of the module).
     pub fn apply<C, M>(self, context: C, processor: &mut Processor<M>)
     where
         M: Debug + Module + ModuleSource<Context = C>,
     {
-        let module = M::get(&self.module, context);
+        if let Some(module) = M::get(&self.module, context, self.config) {
+            processor.add(self.instance, module);
+        }
+    }
+}
+
+// Bypass
 
-        processor.add(self.instance, module);
+#[derive(new, Clone, Debug)]
+pub struct ProtocolBypass {
+    #[new(into)]
+    instance: Uuid,
+    bypass: bool,
+}
+
+impl ProtocolBypass {
+    pub fn apply<M>(self, processor: &mut Processor<M>)
+    where
+        M: Module,
+    {
+        processor.bypass(&self.instance, self.bypass);
     }
 }
 
@@ -69,8 +172,10 @@ impl ProtocolAdd {
 
 #[derive(new, Clone, Debug)]
 pub struct ProtocolConnect {
+    #[new(into)]
     input_instance: Uuid,
     input_port: usize,
+    #[new(into)]
     output_instance: Uuid,
     output_port: usize,
 }
@@ -91,3 +196,142 @@ impl ProtocolConnect {
         }
     }
 }
+
+// Disconnect
+
+#[derive(new, Clone, Debug)]
+pub struct ProtocolDisconnect {
+    #[new(into)]
+    input_instance: Uuid,
+    input_port: usize,
+}
+
+impl ProtocolDisconnect {
+    pub fn apply<M>(self, processor: &mut Processor<M>)
+    where
+        M: Module,
+    {
+        unsafe {
+            processor.disconnect(self.input_instance, self.input_port);
+        }
+    }
+}
+
+// Mute
+
+#[derive(new, Clone, Debug)]
+pub struct ProtocolMute {
+    #[new(into)]
+    instance: Uuid,
+    mute: bool,
+}
+
+impl ProtocolMute {
+    pub fn apply<M>(self, processor: &mut Processor<M>)
+    where
+        M: Module,
+    {
+        processor.mute(&self.instance, self.mute);
+    }
+}
+
+// Remove
+
+#[derive(new, Clone, Debug)]
+pub struct ProtocolRemove {
+    #[new(into)]
+    instance: Uuid,
+}
+
+impl ProtocolRemove {
+    pub fn apply<M>(self, processor: &mut Processor<M>)
+    where
+        M: Debug + Module,
+    {
+        processor.remove(&self.instance);
+    }
+}
+
+// Replace
+
+#[derive(new, Clone, Debug)]
+pub struct ProtocolReplace {
+    #[new(into)]
+    instance: Uuid,
+    module: Uuid,
+    #[new(default)]
+    config: Option<ModuleConfig>,
+}
+
+impl ProtocolReplace {
+    /// Sets the instantiation-time configuration of the new instance, which
+    /// otherwise has the default configuration of the module. The
+    /// configuration is deserialized before sending (see `ModuleConfig`).
+    #[must_use]
+    pub fn with_config(mut self, config: ModuleConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Applies the replace, which is ignored if the module is not found (or the
+    /// configuration is not a configuration of the module).
+    pub fn apply<C, M>(self, context: C, processor: &mut Processor<M>)
+    where
+        M: Debug + Module + ModuleSource<Context = C>,
+    {
+        if let Some(module) = M::get(&self.module, context, self.config) {
+            processor.replace(&self.instance, module);
+        }
+    }
+}
+
+// Reset
+
+#[derive(new, Clone, Debug, Default)]
+pub struct ProtocolReset {}
+
+impl ProtocolReset {
+    pub fn apply<M>(self, processor: &mut Processor<M>)
+    where
+        M: Debug + Module,
+    {
+        processor.reset();
+    }
+}
+
+// Restore
+
+#[derive(new, Clone, Debug)]
+pub struct ProtocolRestore {
+    #[new(into)]
+    instance: Uuid,
+    snapshot: Value,
+}
+
+impl ProtocolRestore {
+    pub fn apply<M>(self, processor: &mut Processor<M>)
+    where
+        M: Module,
+    {
+        processor.restore(&self.instance, self.snapshot);
+    }
+}
+
+// Snapshot
+
+/// Sends a snapshot of the state of every instance to the pending value paired
+/// with the given value (the reply can only be sent once, so a snapshot can
+/// not be cloned).
+#[derive(new, Debug)]
+pub struct ProtocolSnapshot {
+    value: sync::Value<Vec<(Uuid, Value)>>,
+}
+
+impl ProtocolSnapshot {
+    pub fn apply<M>(self, processor: &Processor<M>)
+    where
+        M: Module,
+    {
+        self.value.set(processor.snapshot());
+    }
+}
@@ -0,0 +1,195 @@
+//! # Switch
+//!
+//! The `switch` module provides glitch-free switching between whole patches. A
+//! `Switch` wraps the running processor, and receives replacement processors
+//! which have been prepared elsewhere (typically by loading a patch and
+//! applying the resulting protocol messages on a background thread, before any
+//! processing takes place).
+//!
+//! When a replacement is received, the switch crossfades from the running
+//! processor to the replacement over the requested duration, processing both
+//! for the length of the fade. The fade is applied to the output gain of each
+//! processor (see `ProcessArgs::gain`), which is applied by audio outputs
+//! before the outputs of both processors are summed. Once the previous
+//! processor is silent, it is returned to the sender to be torn down, so that
+//! no deallocation (or joining of worker threads) happens on the processing
+//! thread.
+
+use std::{
+    fmt::Debug,
+    time::Duration,
+};
+
+use fancy_constructor::new;
+use open_modular_core::Vector;
+use rtrb::{
+    Consumer,
+    Producer,
+    PushError,
+    RingBuffer,
+};
+
+use crate::{
+    module::{
+        Module,
+        ModuleSource,
+    },
+    processor::Processor,
+};
+
+// =================================================================================================
+// Switch
+// =================================================================================================
+
+#[derive(Debug, Default)]
+pub struct Switches {}
+
+impl Switches {
+    /// Creates a new switch, running the given processor, and a sender which
+    /// can be used to send replacement processors to the switch.
+    #[must_use]
+    pub fn split<M>(self, processor: Processor<M>) -> (SwitchSender<M>, Switch<M>)
+    where
+        M: Module,
+    {
+        let prepared = RingBuffer::new(4);
+        let retired = RingBuffer::new(4);
+        let sender = SwitchSender::new(prepared.0, retired.1);
+        let switch = Switch::new(processor, prepared.1, retired.0);
+
+        (sender, switch)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Switch
+
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct Switch<M>
+where
+    M: Module,
+{
+    current: Processor<M>,
+    prepared: Consumer<SwitchPrepared<M>>,
+    #[new(default)]
+    previous: Option<Processor<M>>,
+    retired: Producer<Processor<M>>,
+}
+
+impl<M> Switch<M>
+where
+    M: Module,
+{
+    /// Processes a single iteration of the running processor (and of the
+    /// previous processor, while a switch is in progress). A received
+    /// replacement processor is only adopted once any switch in progress has
+    /// completed.
+    pub fn process<C>(&mut self, context: &C, iteration: u64, output: &mut [Vector])
+    where
+        C: Clone,
+        M: Debug + ModuleSource<Context = C>,
+    {
+        if self.previous.is_none()
+            && let Ok(prepared) = self.prepared.pop()
+        {
+            let mut previous = std::mem::replace(&mut self.current, prepared.processor);
+
+            previous.fade(0., prepared.duration);
+
+            self.current.fade(1., prepared.duration);
+            self.previous = Some(previous);
+        }
+
+        if let Some(previous) = &mut self.previous {
+            previous.process(context, iteration, output);
+
+            // The previous processor is only retired once an iteration has
+            // been processed with zero gain throughout, so that any output it
+            // has written outside of the processor is silent. If the retired
+            // queue is full, the previous processor continues to be processed
+            // (silently) until it can be retired.
+
+            if previous.is_faded_out()
+                && let Some(previous) = self.previous.take()
+                && let Err(PushError::Full(previous)) = self.retired.push(previous)
+            {
+                self.previous = Some(previous);
+            }
+        }
+
+        self.current.process(context, iteration, output);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Sender
+
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct SwitchSender<M>
+where
+    M: Module,
+{
+    prepared: Producer<SwitchPrepared<M>>,
+    retired: Consumer<Processor<M>>,
+}
+
+impl<M> SwitchSender<M>
+where
+    M: Module,
+{
+    /// Sends a replacement processor to the switch, to be crossfaded to over
+    /// the given duration. The output gain of the processor is set to zero
+    /// before sending. If the switch queue is full, the processor is returned.
+    pub fn send(
+        &mut self,
+        mut processor: Processor<M>,
+        duration: Duration,
+    ) -> Option<Processor<M>> {
+        processor.fade(0., Duration::ZERO);
+
+        self.prepared
+            .push(SwitchPrepared::new(duration, processor))
+            .map_or_else(
+                |PushError::Full(prepared)| Some(prepared.processor),
+                |()| None,
+            )
+    }
+
+    /// Takes the earliest processor retired by the switch (once faded out)
+    /// which has not yet been taken, if any. The processor is then owned by the
+    /// caller, so is torn down on the calling thread when dropped.
+    pub fn retire(&mut self) -> Option<Processor<M>> {
+        self.retired.pop().ok()
+    }
+
+    /// Tears down (drops) any processors which have been retired by the switch
+    /// since the last collection, returning the number collected. This should
+    /// be called periodically, from a thread other than the processing thread.
+    pub fn collect(&mut self) -> usize {
+        let mut collected = 0;
+
+        while let Some(processor) = self.retire() {
+            drop(processor);
+            collected += 1;
+        }
+
+        collected
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Prepared
+
+#[derive(new, Debug)]
+struct SwitchPrepared<M>
+where
+    M: Module,
+{
+    duration: Duration,
+    processor: Processor<M>,
+}
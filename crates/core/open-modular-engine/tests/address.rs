@@ -0,0 +1,201 @@
+mod common;
+
+use open_modular_engine::{
+    address::{
+        AddressAdd,
+        AddressConnect,
+        AddressDisconnect,
+        AddressError,
+        AddressRemove,
+        AddressResolver,
+    },
+    catalog::ModuleCatalog as _,
+    protocol::Protocol,
+};
+use serde_json::json;
+use uuid::{
+    Uuid,
+    uuid,
+};
+
+use self::common::{
+    Module,
+    fixture,
+};
+
+// =================================================================================================
+// Address
+// =================================================================================================
+
+static SINE: Uuid = uuid!("f75487a4-7847-43f9-ab47-71bd6acfb78d");
+static MULT: Uuid = uuid!("54d93000-7dd2-45ce-a3f1-ad53b0a04fac");
+static OSC: Uuid = uuid!("0e6c7a52-3b8f-4d19-a2e4-7c5f1b9d3e61");
+
+/// Gets a resolver with the instances and connections of the labelled
+/// fixture registered (a sine, labelled "osc", connected to the input of an
+/// eight-way multiple, labelled "mult").
+fn resolver() -> AddressResolver<Module<()>> {
+    let mut resolver = AddressResolver::new(Module::<()>::catalog());
+
+    resolver
+        .register_patch(&fixture("labelled.json"))
+        .expect("patch to register");
+
+    resolver
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Register
+
+#[test]
+fn register_duplicate_instance() {
+    let mut resolver = resolver();
+
+    assert!(matches!(
+        resolver.register("other", OSC, SINE, None),
+        Err(AddressError::InstanceExists { instance }) if instance == OSC
+    ));
+}
+
+#[test]
+fn register_duplicate_label() {
+    let mut resolver = resolver();
+
+    assert!(matches!(
+        resolver.register("osc", Uuid::new_v4(), SINE, None),
+        Err(AddressError::LabelExists { label }) if label == "osc"
+    ));
+}
+
+#[test]
+fn register_invalid_config() {
+    let mut resolver = resolver();
+
+    assert!(matches!(
+        resolver.register("other", Uuid::new_v4(), MULT, Some(json!({ "outputs": "8" }))),
+        Err(AddressError::Config { label, .. }) if label == "other"
+    ));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Add
+
+#[test]
+fn add_config() {
+    let mut resolver = resolver();
+
+    resolver
+        .resolve(AddressAdd::new("other", "util/mult").with_config(json!({ "outputs": 2 })))
+        .expect("add to resolve");
+
+    assert!(matches!(
+        resolver.resolve(AddressConnect::new("other", "Input", "mult", "Output 1")),
+        Ok(Protocol::Connect(_))
+    ));
+
+    assert!(matches!(
+        resolver.resolve(AddressConnect::new("mult", "Input", "other", "Output 2")),
+        Err(AddressError::PortNotFound { label, .. }) if label == "other"
+    ));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Connect
+
+#[test]
+fn connect_configured_port() {
+    let mut resolver = resolver();
+
+    resolver
+        .resolve(AddressAdd::new("other", "util/mult"))
+        .expect("add to resolve");
+
+    // The catalogued (default) multiple has four outputs, where the registered
+    // instance is configured with eight.
+
+    assert!(matches!(
+        resolver.resolve(AddressConnect::new("other", "Input", "mult", "Output 6")),
+        Ok(Protocol::Connect(_))
+    ));
+}
+
+#[test]
+fn connect_input_connected() {
+    let mut resolver = resolver();
+
+    resolver
+        .resolve(AddressAdd::new("other", "oscillator/sine"))
+        .expect("add to resolve");
+
+    assert!(matches!(
+        resolver.resolve(AddressConnect::new("mult", "Input", "other", "Output")),
+        Err(AddressError::InputConnected { label, .. }) if label == "mult"
+    ));
+}
+
+#[test]
+fn connect_output_connected() {
+    let mut resolver = resolver();
+
+    resolver
+        .resolve(AddressAdd::new("other", "util/mult"))
+        .expect("add to resolve");
+
+    assert!(matches!(
+        resolver.resolve(AddressConnect::new("other", "Input", "osc", "Output")),
+        Err(AddressError::OutputConnected { label, .. }) if label == "osc"
+    ));
+}
+
+#[test]
+fn connect_after_disconnect() {
+    let mut resolver = resolver();
+
+    resolver
+        .resolve(AddressDisconnect::new("mult", 0))
+        .expect("disconnect to resolve");
+
+    assert!(matches!(
+        resolver.resolve(AddressConnect::new("mult", 0, "osc", 0)),
+        Ok(Protocol::Connect(_))
+    ));
+}
+
+#[test]
+fn connect_after_remove() {
+    let mut resolver = resolver();
+
+    resolver
+        .resolve(AddressRemove::new("osc"))
+        .expect("remove to resolve");
+
+    resolver
+        .resolve(AddressAdd::new("osc", "oscillator/sine"))
+        .expect("add to resolve");
+
+    assert!(matches!(
+        resolver.resolve(AddressConnect::new("mult", 0, "osc", 0)),
+        Ok(Protocol::Connect(_))
+    ));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Disconnect
+
+#[test]
+fn disconnect_not_connected() {
+    let mut resolver = resolver();
+
+    resolver
+        .resolve(AddressDisconnect::new("mult", 0))
+        .expect("disconnect to resolve");
+
+    assert!(matches!(
+        resolver.resolve(AddressDisconnect::new("mult", 0)),
+        Err(AddressError::InputNotConnected { label, .. }) if label == "mult"
+    ));
+}
@@ -0,0 +1,38 @@
+use open_modular_engine::{
+    catalog::{
+        Catalog,
+        CatalogModule,
+    },
+    module::{
+        ModuleDefinition,
+        Version,
+    },
+};
+use uuid::Uuid;
+
+// =================================================================================================
+// Catalog
+// =================================================================================================
+
+#[test]
+fn page_escapes_port_text() {
+    let definition: ModuleDefinition = ModuleDefinition::builder()
+        .name("test/escape")
+        .version(Version::new(1, 0, 0))
+        .with_input(|input| input.name("A | B").description("Either A\nor B (A | B)"))
+        .with_output(|output| output.name("Out"))
+        .into();
+
+    let catalog = Catalog::new(vec![CatalogModule::new(Uuid::new_v4(), definition)]);
+    let page = catalog.pages().next().expect("page to exist").to_string();
+
+    assert!(page.contains("| 0 | A \\| B | Either A<br>or B (A \\| B) |\n"));
+    assert!(page.contains("| 0 | Out |  |\n"));
+
+    // Every row of each table has the same number of (unescaped) cell
+    // separators as the header.
+
+    for row in page.lines().filter(|line| line.starts_with('|')) {
+        assert_eq!(row.replace("\\|", "").matches('|').count(), 4, "{row}");
+    }
+}
@@ -0,0 +1,38 @@
+use std::{
+    fmt::Debug,
+    fs,
+    path::Path,
+};
+
+use open_modular_engine::{
+    module::module_enum,
+    patch::Patch,
+};
+use open_modular_modules_generators::Sine;
+use open_modular_modules_utilities::Multiple;
+
+// =================================================================================================
+// Common
+// =================================================================================================
+
+#[module_enum(id = "1c7e9b42-8d5a-4f36-b0e1-6a2f4c8d9e73")]
+#[derive(Debug)]
+pub enum Module<R>
+where
+    R: Debug,
+{
+    Multiple,
+    Sine,
+}
+
+/// Reads a patch from the named fixture file.
+pub fn fixture(name: &str) -> Patch {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name);
+
+    let json = fs::read_to_string(path).expect("fixture to be read");
+
+    serde_json::from_str(&json).expect("fixture to be deserialized")
+}
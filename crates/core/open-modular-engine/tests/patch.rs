@@ -0,0 +1,208 @@
+mod common;
+
+use open_modular_engine::{
+    catalog::{
+        Catalog,
+        ModuleCatalog as _,
+    },
+    module::Version,
+    patch::PatchError,
+    protocol::Protocol,
+};
+use serde_json::Value;
+use uuid::{
+    Uuid,
+    uuid,
+};
+
+use self::common::{
+    Module,
+    fixture,
+};
+
+// =================================================================================================
+// Patch
+// =================================================================================================
+
+static SINE: Uuid = uuid!("f75487a4-7847-43f9-ab47-71bd6acfb78d");
+static VOICE: Uuid = uuid!("c3f1a8e2-5d47-4b9c-8e26-9a0b7d4c1f58");
+static VOICE_SINE: Uuid = uuid!("8b4e2d71-a93c-4f05-b6d8-1e7c3a5f9d24");
+
+fn catalog() -> Catalog {
+    Module::<()>::catalog()
+}
+
+/// Asserts that a migrated sine state is the version 2 equivalent of a
+/// version 1 state for the block ending at the given frame (the phase at the
+/// start of the next block, at the fixed version 1 frequency and amplitude).
+#[allow(clippy::cast_precision_loss)]
+fn assert_sine_state(state: &Value, frames: usize) {
+    let phase = (frames as f64 / 48000. * 440.).fract();
+
+    assert_eq!(state["amplitude"], 0.15);
+    assert_eq!(state["fine"], 0.);
+    assert_eq!(state["frequency"], 440.);
+    assert!((state["phase"].as_f64().expect("phase") - phase).abs() < 1e-9);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Migrate
+
+#[test]
+fn migrate_sine_v1() {
+    let patch = fixture("sine-v1.json")
+        .migrate::<Module<()>>(&catalog())
+        .expect("patch to migrate");
+
+    let sine = &patch.instances[0];
+
+    assert_eq!(sine.version, Version::new(2, 1, 0));
+    assert_sine_state(sine.state.as_ref().expect("state"), 64);
+    assert_eq!(patch.connections.len(), 1);
+
+    let protocols = patch
+        .load::<Module<()>>(&catalog())
+        .expect("migrated patch to load");
+
+    assert!(matches!(protocols.as_slice(), [
+        Protocol::Add(_),
+        Protocol::Restore(_),
+        Protocol::Add(_),
+        Protocol::Connect(_),
+    ]));
+}
+
+#[test]
+fn migrate_sine_v1_stateless() {
+    let patch = fixture("sine-v1-stateless.json")
+        .migrate::<Module<()>>(&catalog())
+        .expect("patch to migrate");
+
+    // A version 1 sine with no stored state played at the fixed version 1
+    // frequency and amplitude, so is migrated to a state with those controls
+    // (rather than loading with the current defaults).
+
+    let sine = &patch.instances[0];
+
+    assert_eq!(sine.version, Version::new(2, 1, 0));
+    assert_sine_state(sine.state.as_ref().expect("state"), 0);
+
+    let protocols = patch
+        .load::<Module<()>>(&catalog())
+        .expect("migrated patch to load");
+
+    assert!(matches!(protocols.as_slice(), [
+        Protocol::Add(_),
+        Protocol::Restore(_),
+        Protocol::Add(_),
+        Protocol::Connect(_),
+    ]));
+}
+
+#[test]
+fn migrate_sub_patch_v1() {
+    let patch = fixture("sub-patch-v1.json")
+        .migrate::<Module<()>>(&catalog())
+        .expect("patch to migrate");
+
+    let inner = &patch.sub_patches[0].patch.instances[0];
+
+    assert_eq!(inner.version, Version::new(2, 1, 0));
+    assert_sine_state(inner.state.as_ref().expect("inner state"), 64);
+
+    let state = patch.instances[0].state.as_ref().expect("sub-patch state");
+
+    assert_sine_state(&state[VOICE_SINE.to_string()], 128);
+
+    let protocols = patch
+        .load::<Module<()>>(&catalog())
+        .expect("migrated patch to load");
+
+    assert!(matches!(protocols.as_slice(), [
+        Protocol::Add(_),
+        Protocol::Restore(_),
+        Protocol::Add(_),
+        Protocol::Connect(_),
+    ]));
+}
+
+#[test]
+fn migrate_unchanged_when_current() {
+    let patch = fixture("input-connected.json")
+        .migrate::<Module<()>>(&catalog())
+        .expect("patch to migrate");
+
+    assert_eq!(patch.instances[0].version, Version::new(2, 0, 0));
+    assert_eq!(patch.connections.len(), 2);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Load
+
+#[test]
+fn load_sine_v1_incompatible() {
+    let error = fixture("sine-v1.json")
+        .load::<Module<()>>(&catalog())
+        .expect_err("unmigrated patch to be refused");
+
+    assert!(matches!(
+        error,
+        PatchError::IncompatibleVersion { module, saved, current, .. }
+            if module == SINE && saved == Version::new(1, 0, 0) && current == Version::new(2, 1, 0)
+    ));
+}
+
+#[test]
+fn load_sub_patch_v1_incompatible() {
+    let error = fixture("sub-patch-v1.json")
+        .load::<Module<()>>(&catalog())
+        .expect_err("unmigrated sub-patch to be refused");
+
+    assert!(matches!(
+        error,
+        PatchError::IncompatibleVersion { module, .. } if module == SINE
+    ));
+}
+
+#[test]
+fn load_sub_patch_version_incompatible() {
+    let error = fixture("sub-patch-incompatible.json")
+        .migrate::<Module<()>>(&catalog())
+        .and_then(|patch| patch.load::<Module<()>>(&catalog()))
+        .expect_err("older sub-patch version to be refused");
+
+    assert!(matches!(
+        error,
+        PatchError::IncompatibleVersion { module, saved, current, .. }
+            if module == VOICE && saved == Version::new(1, 0, 0) && current == Version::new(2, 0, 0)
+    ));
+}
+
+#[test]
+fn load_invalid_state() {
+    let error = fixture("invalid-state.json")
+        .load::<Module<()>>(&catalog())
+        .expect_err("invalid state to be refused");
+
+    assert!(matches!(error, PatchError::State { module, .. } if module == SINE));
+}
+
+#[test]
+fn load_duplicate_instance() {
+    let error = fixture("duplicate-instance.json")
+        .load::<Module<()>>(&catalog())
+        .expect_err("duplicate instance to be refused");
+
+    assert!(matches!(error, PatchError::DuplicateInstance { .. }));
+}
+
+#[test]
+fn load_input_connected() {
+    let error = fixture("input-connected.json")
+        .load::<Module<()>>(&catalog())
+        .expect_err("second connection to an input to be refused");
+
+    assert!(matches!(error, PatchError::InputConnected { port: 0, .. }));
+}
@@ -0,0 +1,251 @@
+use std::fmt::Debug;
+
+use fancy_constructor::new;
+use open_modular_core::Sample;
+use open_modular_engine::{
+    module::{
+        ModuleDefine,
+        ModuleDefinition,
+        ModuleDefinitionBuilder,
+        ModuleIdentify as _,
+        ModuleInstantiate,
+        Version,
+        module,
+        module_enum,
+    },
+    port::{
+        Port,
+        PortInputDefinition,
+        PortInputVectorGet as _,
+        PortInputs,
+        PortOutputs,
+    },
+    processor::{
+        Process,
+        ProcessArgs,
+    },
+};
+use open_modular_modules_generators::Sine;
+use open_modular_modules_utilities::{
+    Mixer,
+    Multiple,
+};
+use open_modular_testing::{
+    Harness,
+    Playback,
+    PlaybackConfig,
+    Record,
+    Recorder,
+    Recording,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::json;
+
+// =================================================================================================
+// Processor
+// =================================================================================================
+
+/// The number of iterations rendered.
+static ITERATIONS: usize = 64;
+
+/// Renders a patch of four oscillators (each modulated by a shared modulating
+/// oscillator, through a multiple) summed by a mixer, using the given number of
+/// threads.
+fn render(threads: usize) -> Vec<Sample> {
+    let mut harness = Harness::<Module<Recording>>::with_threads(threads);
+
+    let modulator = harness.add(&Sine::<Recording>::id());
+    let multiple = harness.add(&Multiple::<Recording>::id());
+    let mixer = harness.add(&Mixer::<Recording>::id());
+    let recorder = harness.add(&Recorder::<Recording>::id());
+
+    harness.restore(&modulator, state(0.5, 30.));
+    harness.connect(multiple, 0, modulator, 0);
+
+    for (i, frequency) in [220., 330., 440., 550.].into_iter().enumerate() {
+        let oscillator = harness.add(&Sine::<Recording>::id());
+
+        harness.restore(&oscillator, state(0.25, frequency));
+        harness.connect(oscillator, 0, multiple, i);
+        harness.connect(mixer, i, oscillator, 0);
+    }
+
+    harness.connect(recorder, 0, mixer, 0);
+    harness.process(ITERATIONS);
+    harness.samples()
+}
+
+/// Gets the state of an oscillator with the given amplitude and frequency.
+fn state(amplitude: Sample, frequency: Sample) -> serde_json::Value {
+    json!({ "amplitude": amplitude, "fine": 0., "frequency": frequency, "phase": 0. })
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Threads
+
+#[test]
+fn process_threads_identical() {
+    let bits = |samples: Vec<Sample>| samples.into_iter().map(Sample::to_bits).collect::<Vec<_>>();
+
+    let expected = bits(render(1));
+
+    assert!(expected.iter().any(|sample| *sample != 0));
+
+    for threads in [2, 3, 4, 8] {
+        assert_eq!(bits(render(threads)), expected, "{threads} threads");
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Replace
+
+#[test]
+fn replace_rewires_compatible_ports() {
+    let mut harness = Harness::<Module<Recording>>::default();
+
+    let probe = harness.add_with_config(
+        &Probe::<Recording>::id(),
+        &ProbeConfig::new(vec![
+            Some("A".into()),
+            None,
+            Some("B".into()),
+            Some("C".into()),
+        ]),
+    );
+
+    for (port, value) in [1., 2., 3., 4.].into_iter().enumerate() {
+        let source = harness.add_with_config(
+            &Playback::<Recording>::id(),
+            &PlaybackConfig::new(vec![value], true),
+        );
+
+        harness.connect(probe, port, source, 0);
+    }
+
+    harness.process(2);
+
+    assert_eq!(harness.samples()[4..], [1., 2., 3., 4.]);
+
+    // The named inputs A and B are matched by name (at new indices), the
+    // unnamed input is matched by index, and the input C is dropped, as the
+    // input at the same index is now named D.
+
+    harness.replace_with_config(
+        &probe,
+        &Probe::<Recording>::id(),
+        &ProbeConfig::new(vec![
+            Some("B".into()),
+            None,
+            Some("A".into()),
+            Some("D".into()),
+        ]),
+    );
+    harness.process(2);
+
+    assert_eq!(harness.samples()[12..], [3., 2., 1., 0.]);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Module
+
+#[module_enum(id = "5a9c3e17-2b8d-4f64-9e01-c7d4a2b6f835")]
+#[derive(Debug)]
+pub enum Module<R>
+where
+    R: Debug + Record,
+{
+    Mixer,
+    Multiple,
+    Playback,
+    Probe,
+    Recorder,
+    Sine,
+}
+
+// Probe
+
+/// A `Probe` is a sink with the configured (named or unnamed) inputs, which
+/// records the first frame of each input (or zero, where the input is not
+/// connected) each iteration, in port order.
+#[module(id = "d2e64b19-8c3a-4f57-b1d0-6e9a3c7f4b82", config)]
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct Probe<R>
+where
+    R: Debug + Record,
+{
+    config: ProbeConfig,
+    port_inputs: PortInputs,
+    port_outputs: PortOutputs,
+    recording: R,
+}
+
+impl<R> ModuleDefine for Probe<R>
+where
+    R: Debug + Record,
+{
+    fn define(module: ModuleDefinitionBuilder) -> impl Into<ModuleDefinition> {
+        module
+            .name("test/probe")
+            .version(Version::new(1, 0, 0))
+            .sink(true)
+    }
+}
+
+impl<R> ModuleInstantiate for Probe<R>
+where
+    R: Debug + Record,
+{
+    type Config = ProbeConfig;
+    type Context = R;
+
+    fn configure(mut definition: ModuleDefinition, config: &Self::Config) -> ModuleDefinition {
+        definition.inputs = config
+            .inputs
+            .iter()
+            .map(|name| match name {
+                Some(name) => PortInputDefinition::builder().name(name.clone()).into(),
+                None => PortInputDefinition::builder().into(),
+            })
+            .collect();
+
+        definition
+    }
+
+    fn instantiate(
+        context: Self::Context,
+        config: Self::Config,
+        port_inputs: PortInputs,
+        port_outputs: PortOutputs,
+    ) -> Self {
+        Self::new(config, port_inputs, port_outputs, context)
+    }
+}
+
+impl<R> Process for Probe<R>
+where
+    R: Debug + Record,
+{
+    fn process(&mut self, args: &ProcessArgs) {
+        let samples = (0..self.port_inputs.len())
+            .map(|port| match self.port_inputs.vector(port, &args.token) {
+                Some(Port::Connected(input)) => input[0],
+                _ => 0.,
+            })
+            .collect::<Vec<_>>();
+
+        self.recording.record(&samples);
+    }
+}
+
+/// The configuration of a probe (the name of each input, if named).
+#[derive(new, Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ProbeConfig {
+    inputs: Vec<Option<String>>,
+}
@@ -0,0 +1,170 @@
+#![feature(portable_simd)]
+
+use std::{
+    fmt::Debug,
+    time::Duration,
+};
+
+use fancy_constructor::new;
+use open_modular_core::BUFFER_FRAMES;
+use open_modular_engine::{
+    bus::Bus,
+    module::{
+        ModuleDefine,
+        ModuleDefinition,
+        ModuleDefinitionBuilder,
+        ModuleIdentify as _,
+        ModuleInstantiate,
+        ModuleSource as _,
+        Version,
+        module,
+        module_enum,
+    },
+    port::{
+        PortInputs,
+        PortOutputs,
+    },
+    processor::{
+        Process,
+        ProcessArgs,
+        Processor,
+    },
+    switch::Switches,
+};
+use open_modular_testing::{
+    Record,
+    Recording,
+};
+use uuid::Uuid;
+
+// =================================================================================================
+// Switch
+// =================================================================================================
+
+/// The duration of the crossfade (a whole number of iterations, 960 frames).
+static FADE: Duration = Duration::from_millis(20);
+
+/// The number of iterations processed (long enough for the fade to complete,
+/// and the previous processor to be retired).
+static ITERATIONS: u64 = 32;
+
+/// Gets a processor containing a single gain recorder, recording to the given
+/// recording.
+fn processor(recording: &Recording) -> Processor<Module<Recording>> {
+    let mut processor = Processor::new(Bus::default().split().1);
+    let module = Module::get(&Gain::<Recording>::id(), recording.clone(), None);
+
+    processor.add(Uuid::new_v4(), module.expect("module to exist"));
+    processor
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Crossfade
+
+#[test]
+fn switch_crossfades_and_retires() {
+    let (previous, current) = (Recording::default(), Recording::default());
+    let (mut sender, mut switch) = Switches::default().split(processor(&previous));
+
+    assert!(sender.send(processor(&current), FADE).is_none());
+
+    for iteration in 0..ITERATIONS {
+        switch.process(&Recording::default(), iteration, &mut []);
+    }
+
+    let (previous_gains, current_gains) = (previous.samples(), current.samples());
+
+    // Both processors are processed in every iteration of the fade, so the
+    // gains are aligned, and sum to unity throughout (the previous gain is
+    // zero once the previous processor is retired).
+
+    assert!(previous_gains.len() < current_gains.len());
+    assert!(current_gains.ends_with(&[1.; BUFFER_FRAMES]));
+
+    for (i, current_gain) in current_gains.iter().enumerate() {
+        let previous_gain = previous_gains.get(i).copied().unwrap_or_default();
+        let sum = previous_gain + current_gain;
+
+        assert!((sum - 1.).abs() < 1e-12, "frame {i}: gains sum to {sum}");
+    }
+
+    // The retired processor is the previous processor (and still records to
+    // the previous recording when processed).
+
+    let mut retired = sender.retire().expect("previous processor to be retired");
+
+    retired.process(&Recording::default(), ITERATIONS, &mut []);
+
+    assert_eq!(
+        previous.samples().len(),
+        previous_gains.len() + BUFFER_FRAMES
+    );
+    assert!(sender.retire().is_none());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Module
+
+#[module_enum(id = "f1a7d3c9-6e24-4b8a-9d05-3c8e2b7a4f61")]
+#[derive(Debug)]
+pub enum Module<R>
+where
+    R: Debug + Record,
+{
+    Gain,
+}
+
+// Gain
+
+/// A `Gain` is a sink which records the output gain of the processor for each
+/// frame (see `ProcessArgs::gain`).
+#[module(id = "8c4e1b7d-3a92-4f06-b5e8-d2a9c6f3e174")]
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct Gain<R>
+where
+    R: Debug + Record,
+{
+    port_inputs: PortInputs,
+    port_outputs: PortOutputs,
+    recording: R,
+}
+
+impl<R> ModuleDefine for Gain<R>
+where
+    R: Debug + Record,
+{
+    fn define(module: ModuleDefinitionBuilder) -> impl Into<ModuleDefinition> {
+        module
+            .name("test/gain")
+            .version(Version::new(1, 0, 0))
+            .sink(true)
+    }
+}
+
+impl<R> ModuleInstantiate for Gain<R>
+where
+    R: Debug + Record,
+{
+    type Context = R;
+
+    fn instantiate(
+        context: Self::Context,
+        _config: Self::Config,
+        port_inputs: PortInputs,
+        port_outputs: PortOutputs,
+    ) -> Self {
+        Self::new(port_inputs, port_outputs, context)
+    }
+}
+
+impl<R> Process for Gain<R>
+where
+    R: Debug + Record,
+{
+    fn process(&mut self, args: &ProcessArgs) {
+        self.recording.record(args.gain.as_array());
+    }
+}
@@ -15,6 +15,10 @@ pub enum Error {
     },
     #[snafu(display("internal general error: {message}"))]
     General { message: String },
+    #[snafu(transparent)]
+    Patch {
+        source: open_modular_engine::patch::PatchError,
+    },
 }
 
 // -------------------------------------------------------------------------------------------------
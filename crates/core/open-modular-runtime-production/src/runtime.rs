@@ -1,15 +1,38 @@
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    thread,
+    time::Duration,
+};
 
-use open_modular_engine::module::{
-    Module,
-    ModuleSource,
+use crossbeam::channel::{
+    self,
+    Receiver,
+    RecvTimeoutError,
+    Sender,
+};
+use open_modular_engine::{
+    bus::Bus,
+    catalog::{
+        Catalog,
+        ModuleCatalog,
+    },
+    module::{
+        Module,
+        ModuleConfigureSource,
+        ModuleMigrateSource,
+        ModuleSnapshotSource,
+        ModuleSource,
+    },
+    patch::Patch,
+    processor::Processor,
+    switch::Switches,
 };
 use open_modular_io_audio::{
     Api,
     Audio,
     Configuration as AudioConfiguration,
-    Device,
     Stream,
+    StreamActive,
     StreamInactive,
     StreamInfo,
     StreamOutput,
@@ -30,15 +53,58 @@ use crate::{
 // Runtime
 // =================================================================================================
 
+/// The duration of the crossfade from the running patch to a new patch.
+const SWITCH_FADE: Duration = Duration::from_millis(50);
+
+/// The longest interval between collections of retired processors (while no
+/// patch is received).
+const SWITCH_COLLECT: Duration = Duration::from_millis(100);
+
 #[derive(Debug)]
 pub struct Runtime {
     configuration: Configuration,
+    patches: Receiver<Patch>,
 }
 
 impl Runtime {
+    /// Creates a new runtime, and a sender of the patches to run. Each patch
+    /// sent replaces the running patch (crossfading from the running patch),
+    /// and the runtime completes when the sender is dropped.
     #[must_use]
-    pub fn new(configuration: Configuration) -> Self {
-        Self { configuration }
+    pub fn new(configuration: Configuration) -> (Self, Sender<Patch>) {
+        let (sender, patches) = channel::unbounded();
+
+        (
+            Self {
+                configuration,
+                patches,
+            },
+            sender,
+        )
+    }
+}
+
+impl Runtime {
+    /// Prepares a processor running the given patch, applying the protocols of
+    /// the loaded patch directly (on the calling thread, before the processor
+    /// is sent to the switch, so that no protocol is applied on the processing
+    /// thread).
+    fn prepare<M>(context: &Context, catalog: &Catalog, patch: Patch) -> Result<Processor<M>>
+    where
+        M: Debug
+            + Module
+            + ModuleConfigureSource
+            + ModuleMigrateSource
+            + ModuleSnapshotSource
+            + ModuleSource<Context = Context>,
+    {
+        let mut processor = Processor::new(Bus::default().split().1);
+
+        for protocol in patch.migrate::<M>(catalog)?.load::<M>(catalog)? {
+            protocol.apply(context, &mut processor);
+        }
+
+        Ok(processor)
     }
 }
 
@@ -46,13 +112,62 @@ impl runtime::Runtime for Runtime {
     type Context = Context;
     type Error = Error;
 
+    /// Runs the received patches, processing the running patch on the audio
+    /// thread through a switch (see `Switch`). Patches are loaded, and
+    /// processors prepared and torn down, on the calling thread, which runs
+    /// until the sender of patches is dropped.
     fn run<M>(&self) -> Result<()>
     where
-        M: Debug + Module + ModuleSource<Context = Self::Context>,
+        M: Debug
+            + Module
+            + ModuleCatalog
+            + ModuleConfigureSource
+            + ModuleMigrateSource
+            + ModuleSnapshotSource
+            + ModuleSource<Context = Self::Context>
+            + Send
+            + 'static,
     {
+        let catalog = M::catalog();
+        let context = Context::new();
+
+        let (mut sender, mut switch) =
+            Switches::default().split(Processor::<M>::new(Bus::default().split().1));
+
         let audio = RuntimeAudio::new(&self.configuration.audio)?;
 
-        audio.activate(|_data, _info| {})?;
+        let _stream = audio.activate({
+            let context = context.clone();
+            let mut iteration = 0;
+
+            move |_data, _info| {
+                switch.process(&context, iteration, &mut []);
+                iteration += 1;
+            }
+        })?;
+
+        loop {
+            match self.patches.recv_timeout(SWITCH_COLLECT) {
+                Ok(patch) => {
+                    let mut processor = Self::prepare::<M>(&context, &catalog, patch)?;
+
+                    // The switch only adopts a new processor once any switch in
+                    // progress has completed, so the queue of prepared
+                    // processors is only full while switches are requested
+                    // faster than they complete.
+
+                    while let Some(returned) = sender.send(processor, SWITCH_FADE) {
+                        processor = returned;
+                        sender.collect();
+                        thread::sleep(SWITCH_COLLECT);
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            sender.collect();
+        }
 
         Ok(())
     }
@@ -83,12 +198,10 @@ impl RuntimeAudio {
 }
 
 impl RuntimeAudio {
-    fn activate<F>(self, callback: F) -> Result<()>
+    fn activate<F>(self, callback: F) -> Result<Stream<StreamOutput, StreamActive>>
     where
         F: FnMut(&mut [f32], &StreamInfo) + Send + 'static,
     {
-        self.stream.activate(callback)?;
-
-        Ok(())
+        Ok(self.stream.activate(callback)?)
     }
 }
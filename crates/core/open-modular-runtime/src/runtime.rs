@@ -1,8 +1,14 @@
 use std::fmt::Debug;
 
-use open_modular_engine::module::{
-    Module,
-    ModuleSource,
+use open_modular_engine::{
+    catalog::ModuleCatalog,
+    module::{
+        Module,
+        ModuleConfigureSource,
+        ModuleMigrateSource,
+        ModuleSnapshotSource,
+        ModuleSource,
+    },
 };
 
 // =================================================================================================
@@ -13,10 +19,23 @@ pub trait Runtime {
     type Context;
     type Error;
 
-    /// # TODO
+    /// Runs the given module enumeration until the runtime completes. Patches
+    /// are loaded (and processors prepared) off the processing thread, so the
+    /// module enumeration must be catalogued and sendable.
     ///
     /// # Errors
+    ///
+    /// Returns an error if the runtime cannot be started, or fails while
+    /// running.
     fn run<M>(&self) -> Result<(), Self::Error>
     where
-        M: Debug + Module + ModuleSource<Context = Self::Context>;
+        M: Debug
+            + Module
+            + ModuleCatalog
+            + ModuleConfigureSource
+            + ModuleMigrateSource
+            + ModuleSnapshotSource
+            + ModuleSource<Context = Self::Context>
+            + Send
+            + 'static;
 }
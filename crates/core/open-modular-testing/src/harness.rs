@@ -0,0 +1,171 @@
+use std::fmt::Debug;
+
+use open_modular_core::Sample;
+use open_modular_engine::{
+    bus::Bus,
+    module::{
+        Module,
+        ModuleConfigureSource,
+        ModuleSource,
+    },
+    processor::Processor,
+};
+use serde::Serialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::recorder::Recording;
+
+// =================================================================================================
+// Harness
+// =================================================================================================
+
+/// A `Harness` drives a processor of the module enumeration `M` (with a shared
+/// `Recording` as the context of every instance), adding, connecting and
+/// processing instances directly rather than through protocols.
+#[derive(Debug)]
+pub struct Harness<M>
+where
+    M: Module,
+{
+    iteration: u64,
+    processor: Processor<M>,
+    recording: Recording,
+}
+
+impl<M> Harness<M>
+where
+    M: Module,
+{
+    fn from_processor(processor: Processor<M>) -> Self {
+        Self {
+            iteration: 0,
+            processor,
+            recording: Recording::default(),
+        }
+    }
+}
+
+impl<M> Default for Harness<M>
+where
+    M: Module,
+{
+    /// A harness processing on the calling thread alone.
+    fn default() -> Self {
+        Self::from_processor(Processor::new(Bus::default().split().1))
+    }
+}
+
+impl<M> Harness<M>
+where
+    M: Module + Send,
+{
+    /// A harness processing with the given number of threads (see
+    /// `Processor::with_threads`).
+    #[must_use]
+    pub fn with_threads(threads: usize) -> Self {
+        Self::from_processor(Processor::with_threads(Bus::default().split().1, threads))
+    }
+}
+
+impl<M> Harness<M>
+where
+    M: Debug + Module + ModuleConfigureSource + ModuleSource<Context = Recording>,
+{
+    /// Adds a new instance of the module with the given id (with the default
+    /// configuration), returning the id of the instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the module is not found.
+    pub fn add(&mut self, module: &Uuid) -> Uuid {
+        self.add_module(module, None)
+    }
+
+    /// Adds a new instance of the module with the given id and configuration,
+    /// returning the id of the instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the module is not found, or the configuration is not valid
+    /// for the module.
+    pub fn add_with_config(&mut self, module: &Uuid, config: &impl Serialize) -> Uuid {
+        let config = serde_json::to_value(config).expect("config to serialize");
+
+        self.add_module(module, Some(config))
+    }
+
+    fn add_module(&mut self, module: &Uuid, config: Option<Value>) -> Uuid {
+        let instance = Uuid::new_v4();
+        let module = self.module(module, config);
+
+        self.processor.add(instance, module);
+
+        instance
+    }
+
+    /// Replaces the module of an instance with a new instance of the module
+    /// with the given id and configuration (see `Processor::replace`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance or the module is not found, or the configuration
+    /// is not valid for the module.
+    pub fn replace_with_config(&mut self, instance: &Uuid, module: &Uuid, config: &impl Serialize) {
+        let config = serde_json::to_value(config).expect("config to serialize");
+        let module = self.module(module, Some(config));
+
+        self.processor.replace(instance, module);
+    }
+
+    fn module(&self, module: &Uuid, config: Option<Value>) -> M {
+        let config = config.map(|config| {
+            M::config(module, config)
+                .expect("module to exist")
+                .expect("config to be valid")
+        });
+
+        M::get(module, self.recording.clone(), config).expect("module to exist")
+    }
+
+    /// Restores the state of an instance from a snapshot.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance is not found, or the snapshot is not valid for
+    /// the module of the instance.
+    pub fn restore(&mut self, instance: &Uuid, snapshot: Value) {
+        self.processor.restore(instance, snapshot);
+    }
+
+    /// Connects an input port of an instance to an output port of an instance.
+    pub fn connect(
+        &mut self,
+        input_instance: Uuid,
+        input_port: usize,
+        output_instance: Uuid,
+        output_port: usize,
+    ) {
+        // No port is in use between iterations, so connecting is safe.
+
+        unsafe {
+            self.processor
+                .connect(input_instance, input_port, output_instance, output_port);
+        }
+    }
+
+    /// Processes the given number of iterations.
+    pub fn process(&mut self, iterations: usize) {
+        for _ in 0..iterations {
+            self.processor
+                .process(&self.recording, self.iteration, &mut []);
+            self.iteration += 1;
+        }
+    }
+
+    /// Gets a copy of the samples recorded so far (see `Recording::samples`).
+    #[must_use]
+    pub fn samples(&self) -> Vec<Sample> {
+        self.recording.samples()
+    }
+}
@@ -0,0 +1,37 @@
+//! # Testing
+//!
+//! The `testing` crate provides support shared by the integration tests of the
+//! engine and module crates: a `Harness` which drives a processor, a
+//! `Recorder` sink which records the samples of the input to a shared
+//! `Recording`, a `Playback` source which outputs a configured sequence of
+//! samples (an impulse, a gate, or a clock, for example), and spectral analysis
+//! of recorded samples.
+//!
+//! Each test declares a module enumeration including the modules under test,
+//! along with the `Playback` and `Recorder` modules where required, with a
+//! context of `Recording` (or any other context which is `Record`).
+
+#![feature(portable_simd)]
+
+mod harness;
+mod playback;
+mod recorder;
+mod spectrum;
+
+// =================================================================================================
+// Testing
+// =================================================================================================
+
+pub use self::{
+    harness::Harness,
+    playback::{
+        Playback,
+        PlaybackConfig,
+    },
+    recorder::{
+        Record,
+        Recorder,
+        Recording,
+    },
+    spectrum::magnitudes,
+};
@@ -0,0 +1,152 @@
+use std::{
+    fmt::Debug,
+    marker::PhantomData,
+};
+
+use fancy_constructor::new;
+use open_modular_core::{
+    BUFFER_FRAMES,
+    Sample,
+};
+use open_modular_engine::{
+    module::{
+        ModuleDefine,
+        ModuleDefinition,
+        ModuleDefinitionBuilder,
+        ModuleInstantiate,
+        Version,
+        module,
+    },
+    port::{
+        Port,
+        PortInputs,
+        PortOutputVectorGet as _,
+        PortOutputs,
+    },
+    processor::{
+        Process,
+        ProcessArgs,
+    },
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+// =================================================================================================
+// Playback
+// =================================================================================================
+
+/// A `Playback` is a source which outputs the configured samples in order, one
+/// per frame, followed by silence (or by the samples again, where the playback
+/// repeats). A single sample is an impulse, and a repeated sequence of a high
+/// sample followed by low samples is a clock.
+#[module(id = "b4d17e93-2c6f-4a08-9e51-8f3a7c0d2b64", config)]
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct Playback<R>
+where
+    R: Debug,
+{
+    config: PlaybackConfig,
+    #[new(default)]
+    position: usize,
+    port_inputs: PortInputs,
+    port_outputs: PortOutputs,
+    #[new(default)]
+    _r: PhantomData<R>,
+}
+
+impl<R> ModuleDefine for Playback<R>
+where
+    R: Debug,
+{
+    fn define(module: ModuleDefinitionBuilder) -> impl Into<ModuleDefinition> {
+        module
+            .name("test/playback")
+            .version(Version::new(1, 0, 0))
+            .with_output(|output| output.name("Output"))
+    }
+}
+
+impl<R> ModuleInstantiate for Playback<R>
+where
+    R: Debug,
+{
+    type Config = PlaybackConfig;
+    type Context = R;
+
+    fn instantiate(
+        _context: Self::Context,
+        config: Self::Config,
+        port_inputs: PortInputs,
+        port_outputs: PortOutputs,
+    ) -> Self {
+        Self::new(config, port_inputs, port_outputs)
+    }
+}
+
+impl<R> Process for Playback<R>
+where
+    R: Debug,
+{
+    fn process(&mut self, args: &ProcessArgs) {
+        if let Some(Port::Connected(output)) = self.port_outputs.vector(0, &args.token) {
+            for (i, sample) in output.as_mut_array().iter_mut().enumerate() {
+                *sample = self.config.sample(self.position + i);
+            }
+        }
+
+        // The position advances whether or not the output is connected, so that
+        // the playback stays aligned with the iterations processed.
+
+        self.position += BUFFER_FRAMES;
+    }
+
+    fn reset(&mut self) {
+        self.position = 0;
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Config
+
+/// The configuration of a playback (the samples to output, and whether the
+/// samples repeat once output).
+#[derive(new, Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PlaybackConfig {
+    /// The samples to output, in order
+    pub samples: Vec<Sample>,
+    /// Whether the samples repeat
+    pub repeat: bool,
+}
+
+impl PlaybackConfig {
+    /// A configuration of a single sample of the given amplitude.
+    #[must_use]
+    pub fn impulse(amplitude: Sample) -> Self {
+        Self::new(vec![amplitude], false)
+    }
+
+    /// A configuration of a clock with the given period (in frames), high
+    /// (1.0) for the first frame of each period.
+    #[must_use]
+    pub fn clock(period: usize) -> Self {
+        let mut samples = vec![0.; period.max(1)];
+
+        samples[0] = 1.;
+
+        Self::new(samples, true)
+    }
+
+    /// Gets the sample at the given position (in frames).
+    fn sample(&self, position: usize) -> Sample {
+        match (self.samples.len(), self.repeat) {
+            (0, _) => 0.,
+            (len, true) => self.samples[position % len],
+            (_, false) => self.samples.get(position).copied().unwrap_or_default(),
+        }
+    }
+}
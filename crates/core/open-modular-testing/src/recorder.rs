@@ -0,0 +1,134 @@
+use std::{
+    fmt::Debug,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+use fancy_constructor::new;
+use open_modular_core::{
+    BUFFER_FRAMES,
+    Sample,
+};
+use open_modular_engine::{
+    module::{
+        ModuleDefine,
+        ModuleDefinition,
+        ModuleDefinitionBuilder,
+        ModuleInstantiate,
+        Version,
+        module,
+    },
+    port::{
+        Port,
+        PortInputVectorGet as _,
+        PortInputs,
+        PortOutputs,
+    },
+    processor::{
+        Process,
+        ProcessArgs,
+    },
+};
+
+// =================================================================================================
+// Recorder
+// =================================================================================================
+
+/// Represents a context to which a `Recorder` can record samples.
+pub trait Record {
+    fn record(&self, samples: &[Sample]);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Recording
+
+/// A `Recording` is a shared recording of samples, given to every instance as
+/// the context. Where more than one recorder records to the same recording
+/// concurrently (with worker threads) the order of the recorded blocks is not
+/// defined, so a test should record through a single recorder.
+#[derive(Clone, Debug, Default)]
+pub struct Recording(Arc<Mutex<Vec<Sample>>>);
+
+impl Recording {
+    /// Gets a copy of the samples recorded so far.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the recording is poisoned (a recorder panicked while
+    /// recording).
+    #[must_use]
+    pub fn samples(&self) -> Vec<Sample> {
+        self.0.lock().expect("recording to lock").clone()
+    }
+}
+
+impl Record for Recording {
+    fn record(&self, samples: &[Sample]) {
+        self.0
+            .lock()
+            .expect("recording to lock")
+            .extend_from_slice(samples);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Module
+
+/// A `Recorder` is a sink which records a block of the input each iteration
+/// (silence, where the input is not connected).
+#[module(id = "3f8d2c61-7a4e-4b19-9c05-e6b2a8d4f173")]
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct Recorder<R>
+where
+    R: Debug + Record,
+{
+    port_inputs: PortInputs,
+    port_outputs: PortOutputs,
+    recording: R,
+}
+
+impl<R> ModuleDefine for Recorder<R>
+where
+    R: Debug + Record,
+{
+    fn define(module: ModuleDefinitionBuilder) -> impl Into<ModuleDefinition> {
+        module
+            .name("test/recorder")
+            .version(Version::new(1, 0, 0))
+            .sink(true)
+            .with_input(|input| input.name("Input"))
+    }
+}
+
+impl<R> ModuleInstantiate for Recorder<R>
+where
+    R: Debug + Record,
+{
+    type Context = R;
+
+    fn instantiate(
+        context: Self::Context,
+        _config: Self::Config,
+        port_inputs: PortInputs,
+        port_outputs: PortOutputs,
+    ) -> Self {
+        Self::new(port_inputs, port_outputs, context)
+    }
+}
+
+impl<R> Process for Recorder<R>
+where
+    R: Debug + Record,
+{
+    fn process(&mut self, args: &ProcessArgs) {
+        match self.port_inputs.vector(0, &args.token) {
+            Some(Port::Connected(input)) => self.recording.record(input.as_array()),
+            _ => self.recording.record(&[0.; BUFFER_FRAMES]),
+        }
+    }
+}
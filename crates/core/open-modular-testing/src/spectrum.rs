@@ -0,0 +1,29 @@
+use open_modular_core::Sample;
+use rustfft::{
+    FftPlanner,
+    num_complex::Complex,
+};
+
+// =================================================================================================
+// Spectrum
+// =================================================================================================
+
+/// Gets the magnitude of each bin of the spectrum of the given samples, from
+/// DC to the Nyquist frequency inclusive (where the bins are spaced by the
+/// sample rate divided by the number of samples).
+#[must_use]
+pub fn magnitudes(samples: &[Sample]) -> Vec<Sample> {
+    let mut spectrum = samples
+        .iter()
+        .map(|sample| Complex::new(*sample, 0.))
+        .collect::<Vec<_>>();
+
+    FftPlanner::new()
+        .plan_fft_forward(spectrum.len())
+        .process(&mut spectrum);
+
+    spectrum[..=samples.len() / 2]
+        .iter()
+        .map(|bin| bin.norm())
+        .collect()
+}
@@ -0,0 +1,288 @@
+use std::{
+    marker::PhantomData,
+    simd::{
+        StdFloat as _,
+        num::SimdFloat as _,
+    },
+};
+
+use derive_more::with_trait::Debug;
+use fancy_constructor::new;
+use open_modular_core::{
+    BUFFER_FRAMES,
+    SAMPLE_RATE,
+    Sample,
+    Vector,
+};
+use open_modular_engine::{
+    module::{
+        ModuleDefine,
+        ModuleDefinition,
+        ModuleDefinitionBuilder,
+        ModuleInstantiate,
+        ModuleState,
+        Version,
+        module,
+    },
+    port::{
+        Port,
+        PortInputVectorGet as _,
+        PortInputs,
+        PortOutputVectorGet as _,
+        PortOutputs,
+    },
+    processor::{
+        Process,
+        ProcessArgs,
+    },
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+// =================================================================================================
+// Delay
+// =================================================================================================
+
+/// The shortest delay, in frames (the shortest delay for which every sample
+/// read by the interpolation has already been written).
+static FRAMES_MIN: Sample = 2.;
+
+/// The range of the configurable maximum delay time, in seconds.
+static MAX_TIME_RANGE: (Sample, Sample) = (0.01, 60.);
+
+/// The magnitude of feedback at which the feedback is limited (so that the
+/// delay always decays).
+static FEEDBACK_MAX: Sample = 0.99;
+
+#[module(id = "84038c15-5472-4764-87da-e98d5319f232", state)]
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct Delay<R>
+where
+    R: Debug,
+{
+    #[debug(skip)]
+    buffer: Vec<Sample>,
+    max_time: Sample,
+    #[new(default)]
+    state: DelayState,
+    #[new(default)]
+    write: usize,
+
+    port_inputs: PortInputs,
+    port_outputs: PortOutputs,
+    #[debug(skip)]
+    #[new(default)]
+    _r: PhantomData<R>,
+}
+
+impl<R> ModuleDefine for Delay<R>
+where
+    R: Debug,
+{
+    fn define(module: ModuleDefinitionBuilder) -> impl Into<ModuleDefinition> {
+        module
+            .name("effect/delay")
+            .version(Version::new(1, 0, 0))
+            .category("Effect")
+            .description("Delay")
+            .usage(
+                "The delay time is the base time (250ms by default), scaled by the Time input \
+                 (where each volt doubles the time), up to the maximum time set by the \
+                 configuration of the instance (5s by default). The delayed signal is read with \
+                 cubic interpolation, so that the time can be modulated smoothly (for chorus and \
+                 flanging). The Feedback and Mix inputs are added to the feedback (limited to \
+                 just below unity, and negative feedback inverts each repeat) and the mix (from \
+                 0, dry, to 1, wet).",
+            )
+            .author("Andrew Cherry (@kolektiv)")
+            .license("AGPL-3.0-only")
+            .with_tag("delay")
+            .with_tag("effect")
+            .bypass(0, 0)
+            .with_input(|input| input.name("Input"))
+            .with_input(|input| {
+                input
+                    .name("Time")
+                    .description("Delay time (each volt doubles the time)")
+            })
+            .with_input(|input| {
+                input
+                    .name("Feedback")
+                    .description("Feedback (added to the feedback)")
+            })
+            .with_input(|input| input.name("Mix").description("Mix (added to the mix)"))
+            .with_output(|output| {
+                output
+                    .name("Output")
+                    .description("Mix of the input and the delayed signal")
+            })
+            .with_output(|output| output.name("Wet").description("Delayed signal"))
+    }
+}
+
+impl<R> ModuleInstantiate for Delay<R>
+where
+    R: Debug,
+{
+    type Config = DelayConfig;
+    type Context = R;
+
+    /// Instantiates the delay with a buffer long enough for the configured
+    /// maximum time (allocated here, so that processing never allocates).
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    #[allow(clippy::cast_sign_loss)]
+    fn instantiate(
+        _context: Self::Context,
+        config: Self::Config,
+        port_inputs: PortInputs,
+        port_outputs: PortOutputs,
+    ) -> Self {
+        let max_time = config.max_time.clamp(MAX_TIME_RANGE.0, MAX_TIME_RANGE.1);
+        let frames = (max_time * SAMPLE_RATE as Sample).ceil() as usize;
+
+        // The buffer has room for the samples either side of the read
+        // position used by the interpolation, and a length which is a power
+        // of two (so that positions wrap with a mask).
+
+        let buffer = vec![0.; (frames + 4).next_power_of_two()];
+
+        Self::new(buffer, max_time, port_inputs, port_outputs)
+    }
+}
+
+impl<R> ModuleState for Delay<R>
+where
+    R: Debug,
+{
+    type State = DelayState;
+
+    fn state(&self) -> Self::State {
+        self.state
+    }
+
+    fn restore(&mut self, state: Self::State) {
+        self.state = state;
+    }
+}
+
+impl<R> Process for Delay<R>
+where
+    R: Debug,
+{
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    #[allow(clippy::cast_sign_loss)]
+    fn process(&mut self, args: &ProcessArgs) {
+        let input = |port| match self.port_inputs.vector(port, &args.token) {
+            Some(Port::Connected(input)) => Some(*input),
+            _ => None,
+        };
+
+        let dry = input(0).unwrap_or_default();
+
+        let time = input(1).map_or(Vector::splat(self.state.time), |time| {
+            Vector::splat(self.state.time) * time.exp2()
+        });
+
+        let frames = (time * Vector::splat(SAMPLE_RATE as Sample)).simd_clamp(
+            Vector::splat(FRAMES_MIN),
+            Vector::splat(self.max_time * SAMPLE_RATE as Sample),
+        );
+
+        let feedback = (Vector::splat(self.state.feedback) + input(2).unwrap_or_default())
+            .simd_clamp(Vector::splat(-FEEDBACK_MAX), Vector::splat(FEEDBACK_MAX));
+
+        let mix = (Vector::splat(self.state.mix) + input(3).unwrap_or_default())
+            .simd_clamp(Vector::splat(0.), Vector::splat(1.));
+
+        let mask = self.buffer.len() - 1;
+        let mut wet = Vector::splat(0.);
+
+        for i in 0..BUFFER_FRAMES {
+            // The read position is the given number of frames before the write
+            // position, between the sample at the base index and the next.
+
+            let whole = frames[i].floor();
+            let t = 1. - (frames[i] - whole);
+            let base = self.write + self.buffer.len() - whole as usize - 1;
+
+            let x = |offset: usize| self.buffer[(base + offset - 1) & mask];
+
+            wet[i] = interpolate(x(0), x(1), x(2), x(3), t);
+
+            self.buffer[self.write] = dry[i] + feedback[i] * wet[i];
+            self.write = (self.write + 1) & mask;
+        }
+
+        if let Some(Port::Connected(output)) = self.port_outputs.vector(0, &args.token) {
+            *output = dry + (wet - dry) * mix;
+        }
+
+        if let Some(Port::Connected(output)) = self.port_outputs.vector(1, &args.token) {
+            *output = wet;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.buffer.fill(0.);
+        self.write = 0;
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Functions
+
+/// Interpolates between `x0` and `x1` at `t` (in [0, 1]), using a cubic
+/// (Catmull-Rom) Hermite spline through the four samples around the position.
+fn interpolate(xm1: Sample, x0: Sample, x1: Sample, x2: Sample, t: Sample) -> Sample {
+    let c1 = 0.5 * (x1 - xm1);
+    let c2 = xm1 - 2.5 * x0 + 2. * x1 - 0.5 * x2;
+    let c3 = 0.5 * (x2 - xm1) + 1.5 * (x0 - x1);
+
+    ((c3 * t + c2) * t + c1) * t + x0
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Config
+
+/// The configuration of a delay (the maximum delay time, which determines the
+/// length of the buffer allocated for an instance).
+#[derive(new, Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DelayConfig {
+    /// The maximum delay time, in seconds (from 0.01 to 60)
+    max_time: Sample,
+}
+
+impl Default for DelayConfig {
+    /// A default configuration with a maximum delay time of five seconds.
+    fn default() -> Self {
+        Self::new(5.)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// State
+
+/// The state of a delay (the controls, which can be set by restoring a
+/// state).
+#[derive(new, Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct DelayState {
+    /// The base delay time (at 0V), in seconds
+    time: Sample,
+    /// The feedback (negative values invert each repeat)
+    feedback: Sample,
+    /// The mix, from 0 (dry) to 1 (wet)
+    mix: Sample,
+}
+
+impl Default for DelayState {
+    fn default() -> Self {
+        Self::new(0.25, 0.3, 0.5)
+    }
+}
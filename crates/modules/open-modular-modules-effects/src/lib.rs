@@ -0,0 +1,13 @@
+#![feature(portable_simd)]
+
+mod delay;
+
+// =================================================================================================
+// Effects
+// =================================================================================================
+
+pub use self::delay::{
+    Delay,
+    DelayConfig,
+    DelayState,
+};
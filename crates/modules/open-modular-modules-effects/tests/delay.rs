@@ -0,0 +1,161 @@
+use std::fmt::Debug;
+
+use open_modular_core::{
+    BUFFER_FRAMES,
+    Sample,
+};
+use open_modular_engine::module::{
+    ModuleIdentify as _,
+    module_enum,
+};
+use open_modular_modules_effects::{
+    Delay,
+    DelayConfig,
+};
+use open_modular_testing::{
+    Harness,
+    Playback,
+    PlaybackConfig,
+    Record,
+    Recorder,
+    Recording,
+};
+use serde_json::json;
+
+// =================================================================================================
+// Delay
+// =================================================================================================
+
+/// The number of frames rendered.
+static FRAMES: usize = 2048;
+
+/// A delay of 375 frames (a time of 2^-7 seconds, which is exact, so that the
+/// delay in frames is exactly a whole number).
+static DELAY: usize = 375;
+
+/// The port of the Wet output.
+static PORT_WET: usize = 1;
+
+/// Renders the wet output of a delay with the given configuration, time, and
+/// feedback, given an impulse at the first frame, returning the frames aligned
+/// with the impulse (the impulse reaches the delay one iteration after it is
+/// output, and the output reaches the recorder one iteration later).
+fn render(config: DelayConfig, time: Sample, feedback: Sample) -> Vec<Sample> {
+    let mut harness = Harness::<Module<Recording>>::default();
+
+    let delay = harness.add_with_config(&Delay::<Recording>::id(), &config);
+    let impulse =
+        harness.add_with_config(&Playback::<Recording>::id(), &PlaybackConfig::impulse(1.));
+    let recorder = harness.add(&Recorder::<Recording>::id());
+
+    harness.restore(
+        &delay,
+        json!({ "time": time, "feedback": feedback, "mix": 1. }),
+    );
+    harness.connect(delay, 0, impulse, 0);
+    harness.connect(recorder, 0, delay, PORT_WET);
+    harness.process(FRAMES.div_ceil(BUFFER_FRAMES) + 2);
+
+    harness.samples()[2 * BUFFER_FRAMES..2 * BUFFER_FRAMES + FRAMES].to_vec()
+}
+
+/// Gets the time (in seconds) of the given number of frames.
+fn time(frames: Sample) -> Sample {
+    frames / 48000.
+}
+
+/// Asserts that the given output is the given value at each of the given
+/// frames, and zero elsewhere.
+fn assert_impulses(output: &[Sample], impulses: &[(usize, Sample)]) {
+    for (frame, value) in output.iter().enumerate() {
+        let expected = impulses
+            .iter()
+            .find(|(impulse, _)| *impulse == frame)
+            .map_or(0., |(_, value)| *value);
+
+        assert!(
+            (value - expected).abs() < 1e-9,
+            "frame {frame}: {value} (expected {expected})"
+        );
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Time
+
+#[test]
+#[allow(clippy::cast_precision_loss)]
+fn impulse_integer_time() {
+    let output = render(DelayConfig::default(), time(DELAY as Sample), 0.);
+
+    assert_impulses(&output, &[(DELAY, 1.)]);
+}
+
+#[test]
+#[allow(clippy::cast_precision_loss)]
+fn impulse_fractional_time() {
+    // Half way between frames, the cubic (Catmull-Rom) interpolation of an
+    // impulse is spread over the four frames around the delay.
+
+    let output = render(DelayConfig::default(), time(DELAY as Sample + 0.5), 0.);
+
+    assert_impulses(&output, &[
+        (DELAY - 1, -0.0625),
+        (DELAY, 0.5625),
+        (DELAY + 1, 0.5625),
+        (DELAY + 2, -0.0625),
+    ]);
+}
+
+#[test]
+fn time_clamped_to_max_time() {
+    // A time beyond the maximum time of the configuration is limited to the
+    // maximum time (10ms, 480 frames), as is a maximum time below the range
+    // of the configuration.
+
+    for max_time in [0.01, 0.001] {
+        let output = render(DelayConfig::new(max_time), 1., 0.);
+
+        assert_impulses(&output, &[(480, 1.)]);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Feedback
+
+#[test]
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+#[allow(clippy::cast_precision_loss)]
+fn feedback_decays() {
+    // Each repeat is the previous repeat scaled by the feedback (so that
+    // negative feedback inverts each repeat), including feedback beyond the
+    // limit, which is limited to 0.99.
+
+    for feedback in [0.5, -0.5, 2.] {
+        let output = render(DelayConfig::default(), time(DELAY as Sample), feedback);
+        let gain = feedback.clamp(-0.99, 0.99);
+
+        let impulses = (1..=FRAMES / DELAY)
+            .map(|repeat| (repeat * DELAY, gain.powi(repeat as i32 - 1)))
+            .collect::<Vec<_>>();
+
+        assert_impulses(&output, &impulses);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Module
+
+#[module_enum(id = "a5d3f7e1-6c28-4b94-8e0a-2f9c1d7b4e63")]
+#[derive(Debug)]
+pub enum Module<R>
+where
+    R: Debug + Record,
+{
+    Delay,
+    Playback,
+    Recorder,
+}
@@ -0,0 +1,110 @@
+use std::{
+    f64::consts::PI,
+    simd::{
+        StdFloat as _,
+        num::SimdFloat as _,
+    },
+};
+
+use open_modular_core::{
+    SAMPLE_RATE,
+    Sample,
+    Vector,
+};
+use open_modular_engine::{
+    module::ModuleDefinitionBuilder,
+    port::{
+        Port,
+        PortInputVectorGet as _,
+        PortInputs,
+    },
+    processor::ProcessToken,
+};
+
+// =================================================================================================
+// Filter
+// =================================================================================================
+
+/// The lowest cutoff frequency, in Hz.
+static CUTOFF_MIN: Sample = 5.;
+
+/// The highest cutoff frequency, as a proportion of the sample rate (kept
+/// below the Nyquist frequency, at which the prewarped cutoff is unbounded).
+static CUTOFF_MAX: Sample = 0.45;
+
+/// Adds the common filter inputs (Input, V/Oct, FM, and Resonance, as ports
+/// zero to three) to a module definition.
+pub fn with_inputs(module: ModuleDefinitionBuilder) -> ModuleDefinitionBuilder {
+    module
+        .with_input(|input| input.name("Input"))
+        .with_input(|input| {
+            input
+                .name("V/Oct")
+                .description("Cutoff tracking (1V/oct, 0V = base cutoff)")
+        })
+        .with_input(|input| {
+            input
+                .name("FM")
+                .description("Cutoff modulation (1V/oct, scaled by the FM amount)")
+        })
+        .with_input(|input| {
+            input
+                .name("Resonance")
+                .description("Resonance (added to the resonance, 1V = full range)")
+        })
+}
+
+/// The usage text common to filters using the common inputs.
+pub static USAGE: &str = "The cutoff of the filter is the base cutoff (1kHz by default), offset \
+                          by the V/Oct input (at 1V per octave, for keyboard tracking) and the FM \
+                          input (at 1V per octave, scaled by the FM amount). The Resonance input \
+                          is added to the resonance, which ranges from 0 to 1.";
+
+/// Gets the input for each frame (silent where the input is not connected, so
+/// that the filter continues to decay).
+pub fn input(inputs: &PortInputs, token: &ProcessToken) -> Vector {
+    match inputs.vector(0, token) {
+        Some(Port::Connected(input)) => *input,
+        _ => Vector::splat(0.),
+    }
+}
+
+/// Gets the cutoff frequency (in Hz) for each frame, given the base cutoff and
+/// the amount of the FM input.
+#[allow(clippy::cast_precision_loss)]
+pub fn cutoff(inputs: &PortInputs, token: &ProcessToken, base: Sample, fm: Sample) -> Vector {
+    let mut octaves = Vector::splat(0.);
+
+    if let Some(Port::Connected(pitch)) = inputs.vector(1, token) {
+        octaves += pitch;
+    }
+
+    if let Some(Port::Connected(modulation)) = inputs.vector(2, token) {
+        octaves += modulation * Vector::splat(fm);
+    }
+
+    (Vector::splat(base) * octaves.exp2()).simd_clamp(
+        Vector::splat(CUTOFF_MIN),
+        Vector::splat(SAMPLE_RATE as Sample * CUTOFF_MAX),
+    )
+}
+
+/// Gets the resonance (in [0, 1]) for each frame, given the base resonance.
+pub fn resonance(inputs: &PortInputs, token: &ProcessToken, base: Sample) -> Vector {
+    let resonance = match inputs.vector(3, token) {
+        Some(Port::Connected(resonance)) => Vector::splat(base) + resonance,
+        _ => Vector::splat(base),
+    };
+
+    resonance.simd_clamp(Vector::splat(0.), Vector::splat(1.))
+}
+
+/// Gets the prewarped gain of a zero-delay-feedback integrator for each frame,
+/// given the cutoff (so that the response of the bilinear transform matches
+/// the analogue response exactly at the cutoff).
+#[allow(clippy::cast_precision_loss)]
+pub fn warp(cutoff: &Vector) -> Vector {
+    let angle = cutoff * Vector::splat(PI / SAMPLE_RATE as Sample);
+
+    angle.sin() / angle.cos()
+}
@@ -0,0 +1,200 @@
+use std::marker::PhantomData;
+
+use fancy_constructor::new;
+use open_modular_core::{
+    BUFFER_FRAMES,
+    Sample,
+    Vector,
+};
+use open_modular_engine::{
+    module::{
+        ModuleDefine,
+        ModuleDefinition,
+        ModuleDefinitionBuilder,
+        ModuleInstantiate,
+        ModuleState,
+        Version,
+        module,
+    },
+    port::{
+        Port,
+        PortInputs,
+        PortOutputVectorGet as _,
+        PortOutputs,
+    },
+    processor::{
+        Process,
+        ProcessArgs,
+    },
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::filter;
+
+// =================================================================================================
+// Ladder
+// =================================================================================================
+
+/// The feedback of the ladder at full resonance (the point of self-oscillation
+/// for a four pole ladder).
+static FEEDBACK_MAX: Sample = 4.;
+
+/// The level at which the input to the ladder (after feedback) saturates,
+/// which bounds the amplitude of self-oscillation.
+static SATURATION: Sample = 2.;
+
+#[module(id = "1df0708c-3cd1-44f6-adc2-b20a1e7141cb", state)]
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct Ladder<R> {
+    #[new(default)]
+    stages: [Sample; 4],
+    #[new(default)]
+    state: LadderState,
+
+    port_inputs: PortInputs,
+    port_outputs: PortOutputs,
+    #[new(default)]
+    _r: PhantomData<R>,
+}
+
+impl<R> ModuleDefine for Ladder<R> {
+    fn define(module: ModuleDefinitionBuilder) -> impl Into<ModuleDefinition> {
+        filter::with_inputs(module)
+            .name("filter/ladder")
+            .version(Version::new(1, 0, 0))
+            .category("Filter")
+            .description("Ladder Filter")
+            .usage(format!(
+                "{} The filter is a zero-delay-feedback four pole (24dB/oct) lowpass ladder, with \
+                 a 12dB/oct output taken from the second pole. The filter self-oscillates at a \
+                 resonance of 1, and (as with the analogue original) the passband gain falls as \
+                 the resonance rises.",
+                filter::USAGE
+            ))
+            .author("Andrew Cherry (@kolektiv)")
+            .license("AGPL-3.0-only")
+            .with_tag("filter")
+            .with_tag("ladder")
+            .bypass(0, 0)
+            .with_output(|output| output.name("Lowpass").description("Lowpass (24dB/oct)"))
+            .with_output(|output| {
+                output
+                    .name("Lowpass 12dB")
+                    .description("Lowpass (12dB/oct)")
+            })
+    }
+}
+
+impl<R> ModuleInstantiate for Ladder<R> {
+    type Context = R;
+
+    fn instantiate(
+        _context: Self::Context,
+        _config: Self::Config,
+        port_inputs: PortInputs,
+        port_outputs: PortOutputs,
+    ) -> Self {
+        Self::new(port_inputs, port_outputs)
+    }
+}
+
+impl<R> ModuleState for Ladder<R> {
+    type State = LadderState;
+
+    fn state(&self) -> Self::State {
+        self.state
+    }
+
+    fn restore(&mut self, state: Self::State) {
+        self.state = state;
+    }
+}
+
+impl<R> Process for Ladder<R> {
+    fn process(&mut self, args: &ProcessArgs) {
+        let input = filter::input(&self.port_inputs, &args.token);
+        let cutoff = filter::cutoff(
+            &self.port_inputs,
+            &args.token,
+            self.state.cutoff,
+            self.state.fm,
+        );
+
+        let resonance = filter::resonance(&self.port_inputs, &args.token, self.state.resonance);
+
+        let g = filter::warp(&cutoff);
+        let gain = g / (Vector::splat(1.) + g);
+        let k = resonance * Vector::splat(FEEDBACK_MAX);
+
+        let mut lowpass_4 = Vector::splat(0.);
+        let mut lowpass_2 = Vector::splat(0.);
+
+        for i in 0..BUFFER_FRAMES {
+            let gain = gain[i];
+
+            // The output of the ladder is linear in the input to the first
+            // stage (given the current state of each stage), so the feedback
+            // is resolved without delay before the input saturates.
+
+            let decay = 1. - gain;
+            let sum = self
+                .stages
+                .iter()
+                .fold(0., |sum, stage| sum * gain + stage * decay);
+
+            let output = (gain.powi(4) * input[i] + sum) / (1. + k[i] * gain.powi(4));
+            let mut value = SATURATION * ((input[i] - k[i] * output) / SATURATION).tanh();
+
+            for (j, stage) in self.stages.iter_mut().enumerate() {
+                let v = (value - *stage) * gain;
+
+                value = v + *stage;
+                *stage = value + v;
+
+                if j == 1 {
+                    lowpass_2[i] = value;
+                }
+            }
+
+            lowpass_4[i] = value;
+        }
+
+        if let Some(Port::Connected(output)) = self.port_outputs.vector(0, &args.token) {
+            *output = lowpass_4;
+        }
+
+        if let Some(Port::Connected(output)) = self.port_outputs.vector(1, &args.token) {
+            *output = lowpass_2;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.stages = [0.; 4];
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// State
+
+/// The state of a ladder filter (the controls, which can be set by restoring a
+/// state).
+#[derive(new, Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct LadderState {
+    /// The base cutoff (at 0V), in Hz
+    cutoff: Sample,
+    /// The amount of the FM input, in octaves per volt
+    fm: Sample,
+    /// The resonance, in [0, 1] (self-oscillating at 1)
+    resonance: Sample,
+}
+
+impl Default for LadderState {
+    fn default() -> Self {
+        Self::new(1000., 1., 0.)
+    }
+}
@@ -0,0 +1,20 @@
+#![feature(portable_simd)]
+
+mod filter;
+mod ladder;
+mod svf;
+
+// =================================================================================================
+// Filters
+// =================================================================================================
+
+pub use self::{
+    ladder::{
+        Ladder,
+        LadderState,
+    },
+    svf::{
+        Svf,
+        SvfState,
+    },
+};
@@ -0,0 +1,197 @@
+use std::{
+    marker::PhantomData,
+    simd::StdFloat as _,
+};
+
+use fancy_constructor::new;
+use open_modular_core::{
+    BUFFER_FRAMES,
+    Sample,
+    Vector,
+};
+use open_modular_engine::{
+    module::{
+        ModuleDefine,
+        ModuleDefinition,
+        ModuleDefinitionBuilder,
+        ModuleInstantiate,
+        ModuleState,
+        Version,
+        module,
+    },
+    port::{
+        Port,
+        PortInputs,
+        PortOutputVectorGet as _,
+        PortOutputs,
+    },
+    processor::{
+        Process,
+        ProcessArgs,
+    },
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::filter;
+
+// =================================================================================================
+// SVF
+// =================================================================================================
+
+/// The Q of the filter at full resonance (the Q rises exponentially from 0.5,
+/// with no peak, at a resonance of zero).
+static Q_MAX: Sample = 50.;
+
+#[module(id = "41b299b3-8afc-4ac3-bede-d531c02453db", state)]
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct Svf<R> {
+    #[new(default)]
+    band: Sample,
+    #[new(default)]
+    low: Sample,
+    #[new(default)]
+    state: SvfState,
+
+    port_inputs: PortInputs,
+    port_outputs: PortOutputs,
+    #[new(default)]
+    _r: PhantomData<R>,
+}
+
+impl<R> ModuleDefine for Svf<R> {
+    fn define(module: ModuleDefinitionBuilder) -> impl Into<ModuleDefinition> {
+        filter::with_inputs(module)
+            .name("filter/svf")
+            .version(Version::new(1, 0, 0))
+            .category("Filter")
+            .description("State Variable Filter")
+            .usage(format!(
+                "{} The filter is a zero-delay-feedback (topology-preserving) state variable \
+                 filter, with 12dB/oct lowpass and highpass outputs, and bandpass and notch \
+                 outputs. The Q rises from 0.5 (with no peak) at a resonance of 0, to {Q_MAX} at \
+                 a resonance of 1.",
+                filter::USAGE
+            ))
+            .author("Andrew Cherry (@kolektiv)")
+            .license("AGPL-3.0-only")
+            .with_tag("filter")
+            .with_tag("svf")
+            .bypass(0, 0)
+            .with_output(|output| output.name("Lowpass").description("Lowpass (12dB/oct)"))
+            .with_output(|output| output.name("Highpass").description("Highpass (12dB/oct)"))
+            .with_output(|output| output.name("Bandpass").description("Bandpass (unity gain)"))
+            .with_output(|output| output.name("Notch"))
+    }
+}
+
+impl<R> ModuleInstantiate for Svf<R> {
+    type Context = R;
+
+    fn instantiate(
+        _context: Self::Context,
+        _config: Self::Config,
+        port_inputs: PortInputs,
+        port_outputs: PortOutputs,
+    ) -> Self {
+        Self::new(port_inputs, port_outputs)
+    }
+}
+
+impl<R> ModuleState for Svf<R> {
+    type State = SvfState;
+
+    fn state(&self) -> Self::State {
+        self.state
+    }
+
+    fn restore(&mut self, state: Self::State) {
+        self.state = state;
+    }
+}
+
+impl<R> Process for Svf<R> {
+    fn process(&mut self, args: &ProcessArgs) {
+        let input = filter::input(&self.port_inputs, &args.token);
+        let cutoff = filter::cutoff(
+            &self.port_inputs,
+            &args.token,
+            self.state.cutoff,
+            self.state.fm,
+        );
+
+        let resonance = filter::resonance(&self.port_inputs, &args.token, self.state.resonance);
+
+        // The damping (1 / Q) and coefficients for each frame, following the
+        // trapezoidal integration of the analogue state variable filter.
+
+        let g = filter::warp(&cutoff);
+        let k = Vector::splat(2.) * (resonance * Vector::splat(-(2. * Q_MAX).log2())).exp2();
+        let a1 = Vector::splat(1.) / (Vector::splat(1.) + g * (g + k));
+        let a2 = g * a1;
+        let a3 = g * a2;
+
+        let mut low = Vector::splat(0.);
+        let mut band = Vector::splat(0.);
+
+        for i in 0..BUFFER_FRAMES {
+            let v3 = input[i] - self.low;
+            let v1 = a1[i] * self.band + a2[i] * v3;
+            let v2 = self.low + a2[i] * self.band + a3[i] * v3;
+
+            self.band = 2. * v1 - self.band;
+            self.low = 2. * v2 - self.low;
+
+            band[i] = v1;
+            low[i] = v2;
+        }
+
+        let notch = input - k * band;
+
+        if let Some(Port::Connected(output)) = self.port_outputs.vector(0, &args.token) {
+            *output = low;
+        }
+
+        if let Some(Port::Connected(output)) = self.port_outputs.vector(1, &args.token) {
+            *output = notch - low;
+        }
+
+        if let Some(Port::Connected(output)) = self.port_outputs.vector(2, &args.token) {
+            *output = k * band;
+        }
+
+        if let Some(Port::Connected(output)) = self.port_outputs.vector(3, &args.token) {
+            *output = notch;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.band = 0.;
+        self.low = 0.;
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// State
+
+/// The state of a state variable filter (the controls, which can be set by
+/// restoring a state).
+#[derive(new, Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct SvfState {
+    /// The base cutoff (at 0V), in Hz
+    cutoff: Sample,
+    /// The amount of the FM input, in octaves per volt
+    fm: Sample,
+    /// The resonance, in [0, 1]
+    resonance: Sample,
+}
+
+impl Default for SvfState {
+    fn default() -> Self {
+        Self::new(1000., 1., 0.)
+    }
+}
@@ -0,0 +1,269 @@
+use std::fmt::Debug;
+
+use open_modular_core::{
+    BUFFER_FRAMES,
+    Sample,
+};
+use open_modular_engine::module::{
+    ModuleIdentify as _,
+    module_enum,
+};
+use open_modular_modules_filters::{
+    Ladder,
+    Svf,
+};
+use open_modular_testing::{
+    Harness,
+    Playback,
+    PlaybackConfig,
+    Record,
+    Recorder,
+    Recording,
+    magnitudes,
+};
+use serde_json::{
+    Value,
+    json,
+};
+use uuid::Uuid;
+
+// =================================================================================================
+// Response
+// =================================================================================================
+
+/// The number of frames of the impulse response analysed (one second, so that
+/// each bin of the spectrum is exactly 1Hz, and any cutoff in Hz falls on a
+/// bin).
+static FRAMES: usize = 48000;
+
+/// The amplitude of the impulse (small, so that the saturation of the ladder
+/// has no effect, and the response is that of the linear filter).
+static IMPULSE: Sample = 1e-3;
+
+/// The cutoffs at which the responses are tested, in Hz.
+static CUTOFFS: [usize; 3] = [100, 1000, 4000];
+
+/// The cutoffs at which the slopes above the cutoff are tested, in Hz (where
+/// three octaves above the cutoff remains far enough below the Nyquist
+/// frequency that the bilinear transform does not noticeably steepen the
+/// slope).
+static CUTOFFS_SLOPE: [usize; 2] = [100, 1000];
+
+/// Gets the resonance at which the Q of the state variable filter is
+/// `1 / sqrt(2)` (a Butterworth response, at -3dB at the cutoff), where the Q
+/// rises exponentially from 0.5 at a resonance of 0 to 50 at a resonance of 1.
+fn resonance_butterworth() -> Sample {
+    Sample::sqrt(2.).log2() / Sample::log2(100.)
+}
+
+/// Gets the state of a filter with the given cutoff and resonance.
+fn state(cutoff: usize, resonance: Sample) -> Value {
+    json!({ "cutoff": cutoff, "fm": 1., "resonance": resonance })
+}
+
+/// Gets the magnitude response (in dB, by frequency in Hz) of the given output
+/// of a filter with the given state.
+fn response(id: &Uuid, output: usize, state: &Value) -> Vec<Sample> {
+    let mut harness = Harness::<Module<Recording>>::default();
+
+    let filter = harness.add(id);
+    let impulse = harness.add_with_config(
+        &Playback::<Recording>::id(),
+        &PlaybackConfig::impulse(IMPULSE),
+    );
+    let recorder = harness.add(&Recorder::<Recording>::id());
+
+    harness.restore(&filter, state.clone());
+    harness.connect(filter, 0, impulse, 0);
+    harness.connect(recorder, 0, filter, output);
+
+    // The filter is processed one iteration after the impulse, and the
+    // recorder one iteration after the filter.
+
+    harness.process(FRAMES.div_ceil(BUFFER_FRAMES) + 2);
+
+    let samples = harness.samples()[2 * BUFFER_FRAMES..2 * BUFFER_FRAMES + FRAMES]
+        .iter()
+        .map(|sample| *sample / IMPULSE)
+        .collect::<Vec<_>>();
+
+    magnitudes(&samples)
+        .iter()
+        .map(|magnitude| 20. * magnitude.log10())
+        .collect()
+}
+
+/// Asserts that a level (in dB) is within the given tolerance of the expected
+/// level.
+fn assert_level(name: &str, level: Sample, expected: Sample, tolerance: Sample) {
+    assert!(
+        (level - expected).abs() <= tolerance,
+        "{name}: {level:.2}dB (expected {expected:.2}dB +/- {tolerance}dB)"
+    );
+}
+
+/// Gets the level (in dB) of an analogue prototype of the given order (a
+/// cascade of identical one pole lowpass filters) at the given frequency
+/// relative to the cutoff.
+fn one_pole_cascade(order: i32, ratio: Sample) -> Sample {
+    Sample::from(order) * -10. * (1. + ratio * ratio).log10()
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// SVF
+
+#[test]
+fn svf_cutoff() {
+    for cutoff in CUTOFFS {
+        let state = state(cutoff, resonance_butterworth());
+        let level = |output| response(&Svf::<Recording>::id(), output, &state)[cutoff];
+
+        assert_level(&format!("lowpass at {cutoff}Hz"), level(0), -3.01, 0.05);
+        assert_level(&format!("highpass at {cutoff}Hz"), level(1), -3.01, 0.05);
+        assert_level(&format!("bandpass at {cutoff}Hz"), level(2), 0., 0.05);
+
+        let notch = level(3);
+
+        assert!(notch < -60., "notch at {cutoff}Hz: {notch:.2}dB");
+    }
+}
+
+#[test]
+fn svf_slopes() {
+    for cutoff in CUTOFFS {
+        let state = state(cutoff, resonance_butterworth());
+        let [lowpass, highpass, bandpass, notch] =
+            [0, 1, 2, 3].map(|output| response(&Svf::<Recording>::id(), output, &state));
+
+        // Below the cutoff (the highpass rises at 12dB/oct, the bandpass at
+        // 6dB/oct, and the lowpass and notch are flat).
+
+        let (below, further) = (cutoff / 2, cutoff / 4);
+
+        assert_level(
+            "highpass slope",
+            highpass[below] - highpass[further],
+            12.,
+            0.5,
+        );
+        assert_level(
+            "bandpass slope",
+            bandpass[below] - bandpass[further],
+            6.,
+            0.5,
+        );
+        assert_level("lowpass passband", lowpass[cutoff / 10], 0., 0.05);
+        assert_level("notch passband", notch[cutoff / 10], 0., 0.5);
+    }
+
+    for cutoff in CUTOFFS_SLOPE {
+        let state = state(cutoff, resonance_butterworth());
+        let [lowpass, highpass, bandpass, notch] =
+            [0, 1, 2, 3].map(|output| response(&Svf::<Recording>::id(), output, &state));
+
+        // Above the cutoff (the lowpass falls at 12dB/oct, the bandpass at
+        // 6dB/oct, and the highpass and notch are flat).
+
+        let (above, further) = (cutoff * 4, cutoff * 8);
+
+        assert_level("lowpass slope", lowpass[above] - lowpass[further], 12., 1.5);
+        assert_level(
+            "bandpass slope",
+            bandpass[above] - bandpass[further],
+            6.,
+            1.,
+        );
+        assert_level("highpass passband", highpass[further], 0., 0.05);
+        assert_level("notch passband", notch[further], 0., 0.5);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Ladder
+
+#[test]
+fn ladder_cutoff() {
+    for cutoff in CUTOFFS {
+        let state = state(cutoff, 0.);
+
+        // Without resonance, each pole is -3dB at the cutoff.
+
+        let lowpass_4 = response(&Ladder::<Recording>::id(), 0, &state);
+        let lowpass_2 = response(&Ladder::<Recording>::id(), 1, &state);
+
+        assert_level(
+            "lowpass (4 pole) at cutoff",
+            lowpass_4[cutoff],
+            -12.04,
+            0.05,
+        );
+        assert_level("lowpass (2 pole) at cutoff", lowpass_2[cutoff], -6.02, 0.05);
+    }
+}
+
+#[test]
+fn ladder_resonance_attenuation() {
+    for cutoff in CUTOFFS {
+        for resonance in [0., 0.25, 0.5, 0.75] {
+            let state = state(cutoff, resonance);
+
+            // The passband gain falls with the feedback (4 at full resonance)
+            // to 1 / (1 + feedback).
+
+            let expected = -20. * (1. + 4. * resonance).log10();
+
+            let lowpass_4 = response(&Ladder::<Recording>::id(), 0, &state);
+
+            assert_level("lowpass (4 pole) passband", lowpass_4[0], expected, 0.05);
+        }
+    }
+}
+
+#[test]
+fn ladder_slopes() {
+    for cutoff in CUTOFFS_SLOPE {
+        let state = state(cutoff, 0.);
+
+        let lowpass_4 = response(&Ladder::<Recording>::id(), 0, &state);
+        let lowpass_2 = response(&Ladder::<Recording>::id(), 1, &state);
+
+        // An octave from four to eight times the cutoff (approaching 24dB/oct
+        // and 12dB/oct respectively).
+
+        let (above, further) = (cutoff * 4, cutoff * 8);
+
+        let expected_4 = one_pole_cascade(4, 4.) - one_pole_cascade(4, 8.);
+        let expected_2 = one_pole_cascade(2, 4.) - one_pole_cascade(2, 8.);
+
+        assert_level(
+            "lowpass (4 pole) slope",
+            lowpass_4[above] - lowpass_4[further],
+            expected_4,
+            3.,
+        );
+        assert_level(
+            "lowpass (2 pole) slope",
+            lowpass_2[above] - lowpass_2[further],
+            expected_2,
+            1.5,
+        );
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Module
+
+#[module_enum(id = "7c2e9f41-5b8a-4d36-a1e7-3f0b6d9c8e25")]
+#[derive(Debug)]
+pub enum Module<R>
+where
+    R: Debug + Record,
+{
+    Ladder,
+    Playback,
+    Recorder,
+    Svf,
+}
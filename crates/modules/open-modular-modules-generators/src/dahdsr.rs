@@ -0,0 +1,214 @@
+use std::marker::PhantomData;
+
+use derive_more::with_trait::Debug;
+use fancy_constructor::new;
+use open_modular_core::{
+    Sample,
+    Vector,
+};
+use open_modular_engine::{
+    module::{
+        ModuleDefine,
+        ModuleDefinition,
+        ModuleDefinitionBuilder,
+        ModuleInstantiate,
+        ModuleState,
+        Version,
+        module,
+    },
+    port::{
+        Port,
+        PortInputVectorGet as _,
+        PortInputs,
+        PortOutputVectorGet as _,
+        PortOutputs,
+    },
+    processor::{
+        Process,
+        ProcessArgs,
+    },
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::envelope::{
+    self,
+    Envelope,
+    EnvelopeControls,
+    EnvelopeCurve,
+};
+
+// =================================================================================================
+// DAHDSR
+// =================================================================================================
+
+#[module(id = "d4a7e2c9-5b16-4f3e-8a0d-2c9b7e6f1a34", state)]
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct Dahdsr<R>
+where
+    R: Debug,
+{
+    #[new(default)]
+    envelope: Envelope,
+    #[new(default)]
+    state: DahdsrState,
+
+    port_inputs: PortInputs,
+    port_outputs: PortOutputs,
+    #[debug(skip)]
+    #[new(default)]
+    _r: PhantomData<R>,
+}
+
+impl<R> ModuleDefine for Dahdsr<R>
+where
+    R: Debug,
+{
+    fn define(module: ModuleDefinitionBuilder) -> impl Into<ModuleDefinition> {
+        module
+            .name("envelope/dahdsr")
+            .version(Version::new(1, 0, 0))
+            .category("Envelope")
+            .description("DAHDSR Envelope Generator")
+            .usage(
+                "A rising gate (or a rising edge at the Retrigger input while the gate is high) \
+                 starts the delay, after which the attack starts from the current level and the \
+                 level is held at the peak for the hold time before the decay, and a falling gate \
+                 starts the release. Each time CV input scales the time of the stage (each volt \
+                 doubles the time), and the Sustain CV input is added to the sustain level.",
+            )
+            .author("Andrew Cherry (@kolektiv)")
+            .license("AGPL-3.0-only")
+            .with_tag("envelope")
+            .with_tag("modulation")
+            .with_input(|input| input.name("Gate").description("Gate (high above 0V)"))
+            .with_input(|input| {
+                input
+                    .name("Retrigger")
+                    .description("Retrigger (on rising edge, while the gate is high)")
+            })
+            .with_input(|input| input.name("Delay CV").description("Delay time (1V/oct)"))
+            .with_input(|input| input.name("Attack CV").description("Attack time (1V/oct)"))
+            .with_input(|input| input.name("Hold CV").description("Hold time (1V/oct)"))
+            .with_input(|input| input.name("Decay CV").description("Decay time (1V/oct)"))
+            .with_input(|input| {
+                input
+                    .name("Sustain CV")
+                    .description("Sustain level (added to the level)")
+            })
+            .with_input(|input| {
+                input
+                    .name("Release CV")
+                    .description("Release time (1V/oct)")
+            })
+            .with_output(|output| output.name("Output").description("Envelope (0 to 1)"))
+    }
+}
+
+impl<R> ModuleInstantiate for Dahdsr<R>
+where
+    R: Debug,
+{
+    type Context = R;
+
+    fn instantiate(
+        _context: Self::Context,
+        _config: Self::Config,
+        port_inputs: PortInputs,
+        port_outputs: PortOutputs,
+    ) -> Self {
+        Self::new(port_inputs, port_outputs)
+    }
+}
+
+impl<R> ModuleState for Dahdsr<R>
+where
+    R: Debug,
+{
+    type State = DahdsrState;
+
+    fn state(&self) -> Self::State {
+        self.state
+    }
+
+    fn restore(&mut self, state: Self::State) {
+        self.state = state;
+    }
+}
+
+impl<R> Process for Dahdsr<R>
+where
+    R: Debug,
+{
+    fn process(&mut self, args: &ProcessArgs) {
+        let inputs = &self.port_inputs;
+        let token = &args.token;
+
+        let gate = match inputs.vector(0, token) {
+            Some(Port::Connected(gate)) => *gate,
+            _ => Vector::splat(0.),
+        };
+
+        let retrigger = match inputs.vector(1, token) {
+            Some(Port::Connected(retrigger)) => Some(*retrigger),
+            _ => None,
+        };
+
+        let controls = EnvelopeControls::new(
+            envelope::time(inputs, token, 2, self.state.delay),
+            envelope::time(inputs, token, 3, self.state.attack),
+            envelope::time(inputs, token, 4, self.state.hold),
+            envelope::time(inputs, token, 5, self.state.decay),
+            envelope::level(inputs, token, 6, self.state.sustain),
+            envelope::time(inputs, token, 7, self.state.release),
+        );
+
+        // The envelope is advanced whether or not the output is connected, so
+        // that the envelope follows the gate while disconnected (and connecting
+        // the output part way through continues from the current stage).
+
+        let value = self
+            .envelope
+            .process(&gate, retrigger.as_ref(), &controls, self.state.curve);
+
+        if let Some(Port::Connected(output)) = self.port_outputs.vector(0, &args.token) {
+            *output = value;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.envelope = Envelope::default();
+    }
+}
+
+// State
+
+/// The state of a DAHDSR envelope generator (the controls, which can be set by
+/// restoring a state).
+#[derive(new, Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct DahdsrState {
+    /// The delay time, in seconds
+    delay: Sample,
+    /// The attack time, in seconds
+    attack: Sample,
+    /// The hold time, in seconds
+    hold: Sample,
+    /// The decay time, in seconds
+    decay: Sample,
+    /// The sustain level, in [0, 1]
+    sustain: Sample,
+    /// The release time, in seconds
+    release: Sample,
+    /// The curve of every stage
+    #[serde(default)]
+    curve: EnvelopeCurve,
+}
+
+impl Default for DahdsrState {
+    fn default() -> Self {
+        Self::new(0., 0.01, 0., 0.2, 0.5, 0.5, EnvelopeCurve::default())
+    }
+}
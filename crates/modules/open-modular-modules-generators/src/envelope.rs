@@ -0,0 +1,208 @@
+use std::simd::StdFloat as _;
+
+use derive_more::with_trait::Debug;
+use fancy_constructor::new;
+use open_modular_core::{
+    BUFFER_FRAMES,
+    SAMPLE_RATE,
+    Sample,
+    Vector,
+};
+use open_modular_engine::{
+    port::{
+        Port,
+        PortInputVectorGet as _,
+        PortInputs,
+    },
+    processor::ProcessToken,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+// =================================================================================================
+// Envelope
+// =================================================================================================
+
+/// The steepness of exponential curves (the number of time constants covered
+/// by a stage).
+static CURVE: Sample = 4.;
+
+/// An `Envelope` generator, as a state machine of delay, attack, hold, decay,
+/// sustain, and release stages (where an ADSR envelope is an envelope with no
+/// delay or hold). Every stage except sustain runs for the given time from the
+/// value at which the stage was entered, so a retrigger (or a release before
+/// the end of the attack) continues from the current value without a jump.
+/// The envelope is processed a frame at a time, so that gates are handled at
+/// the exact frame at which they change.
+#[derive(new, Clone, Copy, Debug, Default)]
+pub struct Envelope {
+    #[new(default)]
+    gate: bool,
+    #[new(default)]
+    progress: Sample,
+    #[new(default)]
+    retrigger: bool,
+    #[new(default)]
+    stage: EnvelopeStage,
+    #[new(default)]
+    start: Sample,
+    #[new(default)]
+    value: Sample,
+}
+
+impl Envelope {
+    /// Processes a block, given the gate (high above zero), an optional
+    /// retrigger (restarting the envelope on a rising edge while the gate is
+    /// high), and the stage controls for each frame.
+    pub fn process(
+        &mut self,
+        gate: &Vector,
+        retrigger: Option<&Vector>,
+        controls: &EnvelopeControls,
+        curve: EnvelopeCurve,
+    ) -> Vector {
+        let mut output = Vector::splat(0.);
+
+        for i in 0..BUFFER_FRAMES {
+            let gate = gate[i] > 0.;
+            let retrigger = retrigger.is_some_and(|retrigger| retrigger[i] > 0.);
+
+            if gate && (!self.gate || (retrigger && !self.retrigger)) {
+                self.enter(EnvelopeStage::Delay);
+            } else if !gate && self.gate {
+                self.enter(EnvelopeStage::Release);
+            }
+
+            self.gate = gate;
+            self.retrigger = retrigger;
+            self.advance(controls, i, curve);
+
+            output[i] = self.value;
+        }
+
+        output
+    }
+
+    fn enter(&mut self, stage: EnvelopeStage) {
+        self.progress = 0.;
+        self.stage = stage;
+        self.start = self.value;
+    }
+
+    /// Advances the envelope by a frame, moving through any stages with a time
+    /// of zero within the same frame.
+    #[allow(clippy::cast_precision_loss)]
+    fn advance(&mut self, controls: &EnvelopeControls, i: usize, curve: EnvelopeCurve) {
+        let sustain = controls.sustain[i].clamp(0., 1.);
+
+        loop {
+            let (time, target, next) = match self.stage {
+                EnvelopeStage::Idle => return,
+                EnvelopeStage::Sustain => {
+                    self.value = sustain;
+                    return;
+                }
+                EnvelopeStage::Delay => (controls.delay[i], self.start, EnvelopeStage::Attack),
+                EnvelopeStage::Attack => (controls.attack[i], 1., EnvelopeStage::Hold),
+                EnvelopeStage::Hold => (controls.hold[i], 1., EnvelopeStage::Decay),
+                EnvelopeStage::Decay => (controls.decay[i], sustain, EnvelopeStage::Sustain),
+                EnvelopeStage::Release => (controls.release[i], 0., EnvelopeStage::Idle),
+            };
+
+            let frames = time * SAMPLE_RATE as Sample;
+
+            if frames < 1. {
+                self.value = target;
+                self.enter(next);
+                continue;
+            }
+
+            self.progress += 1. / frames;
+
+            // The stage completes at the frame nearest to the end of the time
+            // (so that rounding in the accumulated progress never extends the
+            // stage by a frame).
+
+            if self.progress + 0.5 / frames >= 1. {
+                self.value = target;
+                self.enter(next);
+            } else {
+                self.value = self.start + (target - self.start) * curve.shape(self.progress);
+            }
+
+            return;
+        }
+    }
+}
+
+// Controls
+
+/// The `EnvelopeControls` for each frame of a block: stage times in seconds,
+/// and the sustain level.
+#[derive(new, Debug)]
+pub struct EnvelopeControls {
+    pub delay: Vector,
+    pub attack: Vector,
+    pub hold: Vector,
+    pub decay: Vector,
+    pub sustain: Vector,
+    pub release: Vector,
+}
+
+/// Gets the time of a stage for each frame, given the base time (in seconds)
+/// and an optional time CV input (where each volt doubles the time).
+pub fn time(inputs: &PortInputs, token: &ProcessToken, port: usize, base: Sample) -> Vector {
+    match inputs.vector(port, token) {
+        Some(Port::Connected(cv)) => Vector::splat(base.max(0.)) * cv.exp2(),
+        _ => Vector::splat(base.max(0.)),
+    }
+}
+
+/// Gets the sustain level for each frame, given the base level and an optional
+/// sustain CV input (added to the level).
+pub fn level(inputs: &PortInputs, token: &ProcessToken, port: usize, base: Sample) -> Vector {
+    match inputs.vector(port, token) {
+        Some(Port::Connected(cv)) => Vector::splat(base) + cv,
+        _ => Vector::splat(base),
+    }
+}
+
+// Curve
+
+/// The `EnvelopeCurve` of every stage: linear, or exponential (fast at the
+/// start of the stage and slowing towards the target, as for the charge and
+/// discharge of an analogue envelope).
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvelopeCurve {
+    Linear,
+    #[default]
+    Exponential,
+}
+
+impl EnvelopeCurve {
+    /// Gets the proportion of the change of a stage completed at the given
+    /// progress through the stage (both in [0, 1]).
+    fn shape(self, progress: Sample) -> Sample {
+        match self {
+            Self::Linear => progress,
+            Self::Exponential => (1. - (-CURVE * progress).exp()) / (1. - (-CURVE).exp()),
+        }
+    }
+}
+
+// Stage
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+enum EnvelopeStage {
+    #[default]
+    Idle,
+    Delay,
+    Attack,
+    Hold,
+    Decay,
+    Sustain,
+    Release,
+}
@@ -0,0 +1,346 @@
+use std::{
+    f64::consts::TAU,
+    marker::PhantomData,
+    simd::{
+        Mask,
+        Select as _,
+        StdFloat as _,
+        cmp::SimdPartialOrd as _,
+        num::SimdFloat as _,
+    },
+};
+
+use derive_more::with_trait::Debug;
+use fancy_constructor::new;
+use open_modular_core::{
+    BUFFER_FRAMES,
+    SAMPLE_RATE,
+    Sample,
+    Vector,
+};
+use open_modular_engine::{
+    module::{
+        ModuleDefine,
+        ModuleDefinition,
+        ModuleDefinitionBuilder,
+        ModuleInstantiate,
+        ModuleState,
+        Version,
+        module,
+    },
+    port::{
+        Port,
+        PortInputVectorGet as _,
+        PortInputs,
+        PortOutputVectorGet as _,
+        PortOutputs,
+    },
+    processor::{
+        Process,
+        ProcessArgs,
+    },
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    phase::{
+        self,
+        Edge,
+        Phase,
+    },
+    random::Random,
+};
+
+// =================================================================================================
+// LFO
+// =================================================================================================
+
+static STREAM_RANDOM: u64 = 0x50;
+
+/// The shapes of the LFO, in output order (the bipolar outputs of each shape,
+/// followed by the unipolar outputs in the same order).
+static SHAPES: [&str; 5] = ["Sine", "Triangle", "Saw", "Square", "Random"];
+
+#[module(id = "3f8a6c1e-27d4-4a9b-b5e0-6d1c9f4e2a87", state)]
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct Lfo<R>
+where
+    R: Debug,
+{
+    #[new(default)]
+    clock: Clock,
+    #[new(default)]
+    cycle: u64,
+    #[new(default)]
+    frame: u64,
+    #[new(default)]
+    phase: Phase,
+    #[new(default)]
+    previous: Sample,
+    #[new(default)]
+    reset: Edge,
+    #[new(default)]
+    state: LfoState,
+
+    port_inputs: PortInputs,
+    port_outputs: PortOutputs,
+    #[debug(skip)]
+    #[new(default)]
+    _r: PhantomData<R>,
+}
+
+impl<R> ModuleDefine for Lfo<R>
+where
+    R: Debug,
+{
+    fn define(module: ModuleDefinitionBuilder) -> impl Into<ModuleDefinition> {
+        let module = module
+            .name("modulation/lfo")
+            .version(Version::new(1, 0, 0))
+            .category("Modulation")
+            .description("Low Frequency Oscillator")
+            .usage(
+                "The rate of the LFO is the base frequency (1Hz by default), offset by the Rate \
+                 input (at 1V per octave). A rising edge at the Reset input resets the phase. \
+                 When the Clock input is connected, every rising edge resets the phase, and the \
+                 time between edges replaces the base frequency (so that one cycle of the LFO \
+                 spans one clock period, scaled by the Rate input). The random shape holds a new \
+                 value for each cycle.",
+            )
+            .author("Andrew Cherry (@kolektiv)")
+            .license("AGPL-3.0-only")
+            .with_tag("lfo")
+            .with_tag("modulation")
+            .with_input(|input| {
+                input
+                    .name("Rate")
+                    .description("Rate (1V/oct, 0V = base frequency)")
+            })
+            .with_input(|input| {
+                input
+                    .name("Reset")
+                    .description("Phase reset (on rising edge)")
+            })
+            .with_input(|input| {
+                input
+                    .name("Clock")
+                    .description("Clock (period of one cycle, phase reset on rising edge)")
+            });
+
+        let module = SHAPES.iter().fold(module, |module, shape| {
+            module.with_output(|output| {
+                output
+                    .name(*shape)
+                    .description(format!("{shape} (bipolar, -1 to 1)"))
+            })
+        });
+
+        SHAPES.iter().fold(module, |module, shape| {
+            module.with_output(|output| {
+                output
+                    .name(format!("{shape}+"))
+                    .description(format!("{shape} (unipolar, 0 to 1)"))
+            })
+        })
+    }
+}
+
+impl<R> ModuleInstantiate for Lfo<R>
+where
+    R: Debug,
+{
+    type Context = R;
+
+    fn instantiate(
+        _context: Self::Context,
+        _config: Self::Config,
+        port_inputs: PortInputs,
+        port_outputs: PortOutputs,
+    ) -> Self {
+        Self::new(port_inputs, port_outputs)
+    }
+}
+
+impl<R> ModuleState for Lfo<R>
+where
+    R: Debug,
+{
+    type State = LfoState;
+
+    fn state(&self) -> Self::State {
+        LfoState {
+            phase: self.phase.value,
+            ..self.state
+        }
+    }
+
+    fn restore(&mut self, state: Self::State) {
+        self.phase = Phase::new(phase::wrap_sample(state.phase));
+        self.state = state;
+    }
+}
+
+impl<R> Process for Lfo<R>
+where
+    R: Debug,
+{
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    #[allow(clippy::cast_sign_loss)]
+    fn process(&mut self, args: &ProcessArgs) {
+        let input = |port| match self.port_inputs.vector(port, &args.token) {
+            Some(Port::Connected(input)) => Some(*input),
+            _ => None,
+        };
+
+        let rate = input(0).unwrap_or_default();
+
+        let mut reset = input(1).map_or(Mask::splat(false), |reset| self.reset.rising(&reset));
+
+        let base = if let Some(clock) = input(2) {
+            let (edges, base) = self.clock.rising(&clock, self.frame, self.state.frequency);
+
+            reset |= edges;
+            base
+        } else {
+            self.clock = Clock::default();
+            Vector::splat(self.state.frequency)
+        };
+
+        // The base frequency may differ within the block (where a clock edge
+        // measures a new period), so the rate is applied to a unit frequency
+        // and scaled by the base frequency of each frame.
+
+        let frequency = (phase::frequency(1., &rate, &Vector::splat(0.)) * base)
+            .simd_min(Vector::splat(SAMPLE_RATE as Sample / 2.));
+        let (phase, ..) = self.phase.advance_sync(&frequency, &reset);
+
+        // A new cycle starts at every frame where the phase wraps (or is
+        // reset), and the random shape takes a new value for each
+        // cycle.
+
+        let cycles = phase.simd_lt(phase.shift_elements_right::<1>(self.previous)) | reset;
+        let cycle = Vector::splat(self.cycle as Sample)
+            + phase::scan(cycles.select(Vector::splat(1.), Vector::splat(0.)));
+
+        self.cycle = cycle[BUFFER_FRAMES - 1] as u64;
+        self.frame += BUFFER_FRAMES as u64;
+        self.previous = phase[BUFFER_FRAMES - 1];
+
+        let random = Random::new(self.state.seed);
+
+        for port in 0..SHAPES.len() * 2 {
+            if let Some(Port::Connected(output)) = self.port_outputs.vector(port, &args.token) {
+                let value = match port % SHAPES.len() {
+                    0 => (phase * Vector::splat(TAU)).sin(),
+                    1 => {
+                        let shifted = phase::wrap(&(phase + Vector::splat(0.25)));
+
+                        Vector::splat(1.) - (shifted - Vector::splat(0.5)).abs() * Vector::splat(4.)
+                    }
+                    2 => phase * Vector::splat(2.) - Vector::splat(1.),
+                    3 => phase
+                        .simd_lt(Vector::splat(0.5))
+                        .select(Vector::splat(1.), Vector::splat(-1.)),
+                    _ => random.bipolar(STREAM_RANDOM, &cycle.cast::<u64>()),
+                };
+
+                *output = if port < SHAPES.len() {
+                    value
+                } else {
+                    (value + Vector::splat(1.)) * Vector::splat(0.5)
+                };
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.clock = Clock::default();
+        self.cycle = 0;
+        self.frame = 0;
+        self.phase = Phase::default();
+        self.previous = 0.;
+        self.reset = Edge::default();
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Clock
+
+/// A `Clock` measures the period (in frames) between rising edges of a clock
+/// input, given the frame counter at the start of each block. A newly measured
+/// period applies from the frame of the edge which completes it.
+#[derive(new, Clone, Copy, Debug, Default)]
+struct Clock {
+    #[new(default)]
+    edge: Edge,
+    #[new(default)]
+    last: Option<u64>,
+    #[new(default)]
+    period: Option<u64>,
+}
+
+impl Clock {
+    /// Gets the frames at which the clock input rises, and the base frequency
+    /// of each frame (the frequency of the period measured at that frame, or
+    /// the given frequency where no period has yet been measured).
+    fn rising(
+        &mut self,
+        input: &Vector,
+        frame: u64,
+        frequency: Sample,
+    ) -> (Mask<i64, BUFFER_FRAMES>, Vector) {
+        let edges = self.edge.rising(input);
+        let mut base = Vector::splat(self.frequency(frequency));
+
+        if edges.any() {
+            for i in (0..BUFFER_FRAMES).filter(|i| edges.test(*i)) {
+                let edge = frame + i as u64;
+
+                if let Some(last) = self.last {
+                    self.period = Some(edge - last);
+                    base.as_mut_array()[i..].fill(self.frequency(frequency));
+                }
+
+                self.last = Some(edge);
+            }
+        }
+
+        (edges, base)
+    }
+
+    /// Gets the frequency of the measured period, or the given frequency where
+    /// no period has yet been measured.
+    #[allow(clippy::cast_precision_loss)]
+    fn frequency(&self, frequency: Sample) -> Sample {
+        self.period
+            .map_or(frequency, |period| SAMPLE_RATE as Sample / period as Sample)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// State
+
+/// The state of an LFO, including the controls and seed (which can be set by
+/// restoring a state).
+#[derive(new, Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct LfoState {
+    /// The base frequency (at a rate of 0V), in Hz
+    frequency: Sample,
+    /// The phase at the start of the next block, in [0, 1)
+    phase: Sample,
+    /// The seed of the random shape
+    seed: u64,
+}
+
+impl Default for LfoState {
+    /// A default state at 1Hz, with a new seed (distinct for every instance).
+    fn default() -> Self {
+        Self::new(1., 0., Random::seed())
+    }
+}
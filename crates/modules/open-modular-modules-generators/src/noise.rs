@@ -0,0 +1,378 @@
+use std::{
+    array,
+    marker::PhantomData,
+    simd::{
+        Select as _,
+        Simd,
+        StdFloat as _,
+        cmp::{
+            SimdPartialEq as _,
+            SimdPartialOrd as _,
+        },
+        num::{
+            SimdFloat as _,
+            SimdUint as _,
+        },
+    },
+};
+
+use derive_more::with_trait::Debug;
+use fancy_constructor::new;
+use open_modular_core::{
+    BUFFER_FRAMES,
+    SAMPLE_RATE,
+    Sample,
+    Vector,
+};
+use open_modular_engine::{
+    module::{
+        ModuleDefine,
+        ModuleDefinition,
+        ModuleDefinitionBuilder,
+        ModuleInstantiate,
+        ModuleState,
+        Version,
+        module,
+    },
+    port::{
+        Port,
+        PortInputs,
+        PortOutputVectorGet as _,
+        PortOutputs,
+    },
+    processor::{
+        Process,
+        ProcessArgs,
+    },
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::random::{
+    self,
+    Bits,
+    Random,
+};
+
+// =================================================================================================
+// Noise
+// =================================================================================================
+
+static STREAM_WHITE: u64 = 0x00;
+static STREAM_PINK: u64 = 0x10;
+static STREAM_PINK_ROWS: u64 = 0x11;
+static STREAM_PINK_SLOW_ROWS: u64 = 0x20;
+static STREAM_BROWN: u64 = 0x30;
+static STREAM_VELVET_POSITION: u64 = 0x40;
+static STREAM_VELVET_SIGN: u64 = 0x41;
+
+#[module(id = "5e0c2a7d-91f4-4b6e-8d3a-c47f1e2b9a60", state)]
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct Noise<R>
+where
+    R: Debug,
+{
+    #[new(default)]
+    brown: Brown,
+    #[new(default)]
+    frame: u64,
+    #[new(default)]
+    pink: Pink,
+    #[new(default)]
+    state: NoiseState,
+
+    port_inputs: PortInputs,
+    port_outputs: PortOutputs,
+    #[debug(skip)]
+    #[new(default)]
+    _r: PhantomData<R>,
+}
+
+impl<R> ModuleDefine for Noise<R>
+where
+    R: Debug,
+{
+    fn define(module: ModuleDefinitionBuilder) -> impl Into<ModuleDefinition> {
+        module
+            .name("generator/noise")
+            .version(Version::new(1, 0, 0))
+            .category("Generator")
+            .description("White, Pink, Brown, and Velvet Noise")
+            .usage(
+                "Each output is generated from the seed of the module, so that restoring a state \
+                 (or reloading a patch) with the same seed reproduces the same noise. Velvet \
+                 noise is a sparse sequence of unit impulses of random sign, one at a random \
+                 position within each period given by the density.",
+            )
+            .author("Andrew Cherry (@kolektiv)")
+            .license("AGPL-3.0-only")
+            .with_tag("audio")
+            .with_tag("noise")
+            .with_output(|output| output.name("White").description("Uniform white noise"))
+            .with_output(|output| {
+                output
+                    .name("Pink")
+                    .description("Pink (-3dB/oct) noise (Voss-McCartney)")
+            })
+            .with_output(|output| {
+                output
+                    .name("Brown")
+                    .description("Brown (-6dB/oct) noise (leaky integration)")
+            })
+            .with_output(|output| {
+                output
+                    .name("Velvet")
+                    .description("Velvet (sparse impulse) noise")
+            })
+    }
+}
+
+impl<R> ModuleInstantiate for Noise<R>
+where
+    R: Debug,
+{
+    type Context = R;
+
+    fn instantiate(
+        _context: Self::Context,
+        _config: Self::Config,
+        port_inputs: PortInputs,
+        port_outputs: PortOutputs,
+    ) -> Self {
+        Self::new(port_inputs, port_outputs)
+    }
+}
+
+impl<R> ModuleState for Noise<R>
+where
+    R: Debug,
+{
+    type State = NoiseState;
+
+    fn state(&self) -> Self::State {
+        self.state
+    }
+
+    /// Restores the controls and seed, restarting generation from the start of
+    /// the sequence for the seed.
+    fn restore(&mut self, state: Self::State) {
+        self.state = state;
+        self.reset();
+    }
+}
+
+impl<R> Process for Noise<R>
+where
+    R: Debug,
+{
+    fn process(&mut self, args: &ProcessArgs) {
+        let random = Random::new(self.state.seed);
+        let counter = random::counter(self.frame);
+        let amplitude = Vector::splat(self.state.amplitude);
+
+        // Pink and brown noise are always generated (whether or not the outputs
+        // are connected), so that connecting an output does not change the
+        // sequence.
+
+        let pink = self.pink.process(random, &counter, self.frame);
+        let brown = self.brown.process(&random.bipolar(STREAM_BROWN, &counter));
+
+        if let Some(Port::Connected(output)) = self.port_outputs.vector(0, &args.token) {
+            *output = random.bipolar(STREAM_WHITE, &counter) * amplitude;
+        }
+
+        if let Some(Port::Connected(output)) = self.port_outputs.vector(1, &args.token) {
+            *output = pink * amplitude;
+        }
+
+        if let Some(Port::Connected(output)) = self.port_outputs.vector(2, &args.token) {
+            *output = brown * amplitude;
+        }
+
+        if let Some(Port::Connected(output)) = self.port_outputs.vector(3, &args.token) {
+            *output = velvet(random, &counter, self.state.density) * amplitude;
+        }
+
+        self.frame += BUFFER_FRAMES as u64;
+    }
+
+    fn reset(&mut self) {
+        self.brown = Brown::default();
+        self.frame = 0;
+        self.pink = Pink::default();
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Pink
+
+/// The number of rows summed by the pink noise generator, giving a -3dB/oct
+/// slope down to roughly 1Hz (the lowest row is updated every 2^15 frames).
+const PINK_ROWS: usize = 16;
+
+/// The number of rows updated within a block (rather than at most once per
+/// block, at the first frame).
+const PINK_FAST_ROWS: usize = BUFFER_FRAMES.trailing_zeros() as usize;
+
+/// A `Pink` noise generator, using the Voss-McCartney algorithm: a sum of rows
+/// of held random values, where row `r` is updated every 2^(r + 1) frames
+/// (at the frames where the frame counter has `r` trailing zeros), along with
+/// a white noise value per frame. Rows updated within a block are computed as
+/// a whole vector by gathering the value for the most recent update of each
+/// frame.
+#[derive(Clone, Copy, Debug, Default)]
+struct Pink {
+    rows: [Sample; PINK_ROWS],
+}
+
+impl Pink {
+    #[allow(clippy::cast_possible_truncation)]
+    fn process(&mut self, random: Random, counter: &Bits, frame: u64) -> Vector {
+        let block = frame / BUFFER_FRAMES as u64;
+        let index = Simd::<usize, BUFFER_FRAMES>::from_array(array::from_fn(|i| i));
+        let mut sum = random.bipolar(STREAM_PINK, counter);
+
+        for (row, held) in self.rows.iter_mut().enumerate().take(PINK_FAST_ROWS) {
+            let values = random.bipolar(STREAM_PINK_ROWS + row as u64, counter);
+
+            // The first block has no previous value, so the value for the first
+            // frame (which is never otherwise used) is taken.
+
+            if block == 0 {
+                *held = values[0];
+            }
+
+            let first = 1 << row;
+            let period = Simd::splat(row + 1);
+            let update = ((index.saturating_sub(Simd::splat(first)) >> period) << period)
+                + Simd::splat(first);
+
+            let values = index.simd_lt(Simd::splat(first)).select(
+                Vector::splat(*held),
+                Vector::gather_or_default(values.as_array(), update),
+            );
+
+            *held = values[BUFFER_FRAMES - 1];
+            sum += values;
+        }
+
+        let slow = random.bipolar(STREAM_PINK_SLOW_ROWS, counter);
+
+        for row in PINK_FAST_ROWS..PINK_ROWS {
+            if block == 0 || block.trailing_zeros() as usize == row - PINK_FAST_ROWS {
+                self.rows[row] = slow[row];
+            }
+
+            sum += Vector::splat(self.rows[row]);
+        }
+
+        sum * Vector::splat(0.125)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Brown
+
+/// The leak of the brown noise integrator per frame (a cutoff of roughly 8Hz,
+/// keeping the output centred).
+static BROWN_LEAK: Sample = 0.999;
+
+/// A `Brown` noise generator, using a leaky integration of white noise. The
+/// integration of a block is computed as a log-step weighted prefix sum, with
+/// the value carried from the previous block decayed by the leak for each
+/// frame.
+#[derive(Clone, Copy, Debug)]
+struct Brown {
+    powers: Vector,
+    value: Sample,
+}
+
+impl Brown {
+    fn process(&mut self, white: &Vector) -> Vector {
+        let p = &self.powers;
+        let mut value = *white;
+
+        value += Vector::splat(p[0]) * value.shift_elements_right::<1>(0.);
+        value += Vector::splat(p[1]) * value.shift_elements_right::<2>(0.);
+        value += Vector::splat(p[3]) * value.shift_elements_right::<4>(0.);
+        value += Vector::splat(p[7]) * value.shift_elements_right::<8>(0.);
+        value += Vector::splat(p[15]) * value.shift_elements_right::<16>(0.);
+        value += Vector::splat(p[31]) * value.shift_elements_right::<32>(0.);
+        value += self.powers * Vector::splat(self.value);
+
+        self.value = value[BUFFER_FRAMES - 1];
+
+        // Scaled to an RMS level of roughly 0.3 (matching the pink noise).
+
+        value * Vector::splat(0.3 * (3. * (1. - BROWN_LEAK * BROWN_LEAK)).sqrt())
+    }
+}
+
+impl Default for Brown {
+    /// The powers of the leak for each frame (the leak to the power of one for
+    /// the first frame, and so on).
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn default() -> Self {
+        Self {
+            powers: Vector::from_array(array::from_fn(|i| BROWN_LEAK.powi(i as i32 + 1))),
+            value: 0.,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Velvet
+
+/// Gets a block of velvet noise at the given density (impulses per second).
+/// Each period of `SAMPLE_RATE / density` frames has a single impulse at a
+/// random frame within the period, where the position and sign of the impulse
+/// are derived from the period counter (so that each frame can be computed
+/// independently).
+#[allow(clippy::cast_precision_loss)]
+fn velvet(random: Random, counter: &Bits, density: Sample) -> Vector {
+    let sample_rate = SAMPLE_RATE as Sample;
+    let period = Vector::splat(sample_rate / density.clamp(1., sample_rate / 2.));
+    let frame = counter.cast::<Sample>();
+
+    let index = (frame / period).floor();
+    let start = (index * period).ceil();
+    let end = ((index + Vector::splat(1.)) * period).ceil();
+
+    let index = index.cast::<u64>();
+    let position =
+        start + (random.unipolar(STREAM_VELVET_POSITION, &index) * (end - start)).floor();
+    let sign = (random.bits(STREAM_VELVET_SIGN, &index) & Bits::splat(1))
+        .simd_eq(Bits::splat(0))
+        .select(Vector::splat(1.), Vector::splat(-1.));
+
+    frame.simd_eq(position).select(sign, Vector::splat(0.))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// State
+
+/// The state of a noise generator, including the controls and seed (which can
+/// be set by restoring a state).
+#[derive(new, Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct NoiseState {
+    /// The amplitude (scale) of every output
+    amplitude: Sample,
+    /// The density of the velvet noise, in impulses per second
+    density: Sample,
+    /// The seed of the random number generator
+    seed: u64,
+}
+
+impl Default for NoiseState {
+    /// A default state with a new seed (distinct for every instance).
+    fn default() -> Self {
+        Self::new(1., 2000., Random::seed())
+    }
+}
@@ -0,0 +1,277 @@
+use std::simd::{
+    Mask,
+    Select as _,
+    cmp::SimdPartialOrd as _,
+    num::SimdFloat as _,
+};
+
+use derive_more::with_trait::Debug;
+use fancy_constructor::new;
+use open_modular_core::{
+    BUFFER_FRAMES,
+    Sample,
+    Vector,
+};
+use open_modular_engine::{
+    module::ModuleDefinitionBuilder,
+    port::{
+        Port,
+        PortInputVectorGet as _,
+        PortInputs,
+    },
+    processor::ProcessToken,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::phase::{
+    self,
+    Edge,
+    FREQUENCY_C4,
+    Phase,
+};
+
+// =================================================================================================
+// Oscillator
+// =================================================================================================
+
+/// Adds the common oscillator inputs (V/Oct, Lin FM, Exp FM, and Sync, as
+/// ports zero to three) to a module definition.
+pub fn with_inputs(module: ModuleDefinitionBuilder) -> ModuleDefinitionBuilder {
+    module
+        .with_input(|input| {
+            input
+                .name("V/Oct")
+                .description("Pitch (1V/oct, 0V = base frequency)")
+        })
+        .with_input(|input| {
+            input
+                .name("Lin FM")
+                .description("Linear frequency modulation (1V = base frequency)")
+        })
+        .with_input(|input| {
+            input
+                .name("Exp FM")
+                .description("Exponential frequency modulation (1V/oct)")
+        })
+        .with_input(|input| {
+            input
+                .name("Sync")
+                .description("Hard sync (phase reset on rising edge)")
+        })
+}
+
+/// Adds the common oscillator control inputs (Fine and Amplitude) to a module
+/// definition. The control inputs follow any inputs specific to the module (so
+/// that the ports of existing inputs are unchanged), and the ports are given to
+/// `Oscillator::advance` and `control` respectively.
+pub fn with_control_inputs(module: ModuleDefinitionBuilder) -> ModuleDefinitionBuilder {
+    module
+        .with_input(|input| {
+            input
+                .name("Fine")
+                .description("Fine tuning (1V = 100 cents, added to the fine tuning)")
+        })
+        .with_input(|input| {
+            input
+                .name("Amplitude")
+                .description("Amplitude (1V = full scale, added to the amplitude)")
+        })
+}
+
+/// The usage text common to oscillators using the common inputs.
+pub static USAGE: &str =
+    "The frequency of the oscillator is the base frequency (C4 by default), offset by the fine \
+     tuning (in cents) and the V/Oct and Exp FM inputs (summed, at 1V per octave). Lin FM adds \
+     the base frequency for every volt, and may take the frequency through zero. A rising edge at \
+     the Sync input resets the phase. The Fine and Amplitude inputs are added to the fine tuning \
+     (at 100 cents per volt) and the amplitude (at full scale per volt).";
+
+/// Gets the value of a control for each frame of a block, given the base value
+/// of the control, and the input (if connected) which is added to the base
+/// value at the given scale per volt.
+pub fn control(
+    inputs: &PortInputs,
+    token: &ProcessToken,
+    port: usize,
+    base: Sample,
+    scale: Sample,
+) -> Vector {
+    match inputs.vector(port, token) {
+        Some(Port::Connected(input)) => Vector::splat(base) + input * Vector::splat(scale),
+        _ => Vector::splat(base),
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Oscillator
+
+/// An `Oscillator` drives the phase of an oscillator module from the common
+/// oscillator inputs.
+#[derive(new, Clone, Copy, Debug, Default)]
+pub struct Oscillator {
+    #[new(default)]
+    phase: Phase,
+    #[new(default)]
+    sync: Edge,
+}
+
+impl Oscillator {
+    /// Gets the phase at the start of the next block.
+    pub fn phase(&self) -> Sample {
+        self.phase.value
+    }
+
+    /// Sets the phase at the start of the next block (wrapped to [0, 1)).
+    pub fn set_phase(&mut self, phase: Sample) {
+        self.phase = Phase::new(phase::wrap_sample(phase));
+    }
+
+    /// Resets the phase and sync state.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+impl Oscillator {
+    /// Advances the oscillator by a block, given the base frequency and fine
+    /// tuning (in cents), the common inputs of the module, and the port of the
+    /// Fine input (see `with_control_inputs`).
+    pub fn advance(
+        &mut self,
+        inputs: &PortInputs,
+        token: &ProcessToken,
+        frequency: Sample,
+        fine: Sample,
+        port_fine: usize,
+    ) -> OscillatorBlock {
+        let input = |port| match inputs.vector(port, token) {
+            Some(Port::Connected(input)) => Some(*input),
+            _ => None,
+        };
+
+        let pitch = input(0).unwrap_or_default()
+            + input(2).unwrap_or_default()
+            + control(inputs, token, port_fine, fine, 100.) / Vector::splat(1200.);
+
+        let linear = input(1).unwrap_or_default();
+        let frequency = phase::frequency(frequency, &pitch, &linear);
+
+        if let Some(sync) = input(3) {
+            let reset = self.sync.rising(&sync);
+            let (phase, increment, previous) = self.phase.advance_sync(&frequency, &reset);
+
+            OscillatorBlock::new(phase, increment, previous, reset)
+        } else {
+            let (phase, increment) = self.phase.advance(&frequency);
+
+            OscillatorBlock::new(phase, increment, Vector::splat(0.), Mask::splat(false))
+        }
+    }
+}
+
+/// An `OscillatorBlock` is the phase of an oscillator for each frame of a
+/// block, along with the per-frame increment, and the frames at which the phase
+/// was reset by sync (along with the phase which would have been reached at
+/// those frames).
+#[derive(new, Debug)]
+pub struct OscillatorBlock {
+    pub phase: Vector,
+    pub increment: Vector,
+    pub previous: Vector,
+    pub reset: Mask<i64, BUFFER_FRAMES>,
+}
+
+impl OscillatorBlock {
+    /// Corrects the discontinuities introduced by hard sync in a block of
+    /// output, given a function from phase to (uncorrected) output value. The
+    /// jump at each reset frame is halved, approximating the band-limited step
+    /// at a frame boundary.
+    pub fn correct_sync<F>(&self, output: &mut Vector, f: F)
+    where
+        F: Fn(&Vector) -> Vector,
+    {
+        if self.reset.any() {
+            let jump = f(&Vector::splat(0.)) - f(&self.previous);
+            let correction = jump * Vector::splat(0.5);
+
+            *output -= self.reset.select(correction, Vector::splat(0.));
+        }
+    }
+}
+
+// State
+
+/// The state of an oscillator using the common inputs, including the base
+/// values of the controls (which can be set by restoring a state, and are
+/// modulated by the control inputs, see `with_control_inputs`). By default an
+/// oscillator is at C4 and full scale (an amplitude of 1.0 - version 1 of the
+/// sine oscillator was fixed at 0.15, which is preserved when a version 1 state
+/// is migrated).
+#[derive(new, Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct OscillatorState {
+    /// The amplitude (peak) of the output
+    pub(crate) amplitude: Sample,
+    /// The fine tuning, in cents
+    pub(crate) fine: Sample,
+    /// The base frequency (at a pitch of 0V), in Hz
+    pub(crate) frequency: Sample,
+    /// The phase at the start of the next block, in [0, 1)
+    pub(crate) phase: Sample,
+}
+
+impl Default for OscillatorState {
+    fn default() -> Self {
+        Self::new(1., 0., FREQUENCY_C4, 0.)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Band-Limiting
+
+/// Gets the two-point polynomial band-limited step (`PolyBLEP`) residual for a
+/// unit step (from -1 to 1) at phase zero, for each frame, given the phase and
+/// the per-frame phase increment. The residual is non-zero only within one
+/// increment either side of the step.
+pub fn polyblep(phase: &Vector, increment: &Vector) -> Vector {
+    let increment = increment.abs().simd_max(Vector::splat(Sample::EPSILON));
+    let one = Vector::splat(1.);
+    let zero = Vector::splat(0.);
+
+    let after = *phase / increment;
+    let before = (*phase - one) / increment;
+
+    let after_value = after + after - after * after - one;
+    let before_value = before * before + before + before + one;
+
+    phase.simd_lt(increment).select(
+        after_value,
+        (*phase).simd_gt(one - increment).select(before_value, zero),
+    )
+}
+
+/// Gets the two-point polynomial band-limited ramp (`PolyBLAMP`) residual for a
+/// change of slope of two per frame (the integral of the `PolyBLEP` step) at
+/// phase zero, for each frame, given the phase and the per-frame phase
+/// increment.
+pub fn polyblamp(phase: &Vector, increment: &Vector) -> Vector {
+    let increment = increment.abs().simd_max(Vector::splat(Sample::EPSILON));
+    let one = Vector::splat(1.);
+    let zero = Vector::splat(0.);
+    let third = Vector::splat(1. / 3.);
+
+    let after = *phase / increment - one;
+    let before = (*phase - one) / increment + one;
+
+    let after_value = -third * after * after * after;
+    let before_value = third * before * before * before;
+
+    phase.simd_lt(increment).select(
+        after_value,
+        (*phase).simd_gt(one - increment).select(before_value, zero),
+    )
+}
@@ -0,0 +1,149 @@
+use std::simd::{
+    Mask,
+    StdFloat as _,
+    cmp::SimdPartialOrd as _,
+    num::SimdFloat as _,
+};
+
+use derive_more::with_trait::Debug;
+use fancy_constructor::new;
+use open_modular_core::{
+    BUFFER_FRAMES,
+    SAMPLE_RATE,
+    Sample,
+    Vector,
+};
+
+// =================================================================================================
+// Phase
+// =================================================================================================
+
+/// The frequency of a 1V/oct pitch of 0V (C4).
+pub static FREQUENCY_C4: Sample = 261.625_565_300_598_6;
+
+/// Gets the frequency of each frame, given a base frequency (the frequency at
+/// a pitch of 0V), a pitch in volts (1V/oct), and a linear frequency
+/// modulation in volts (where 1V adds the base frequency). The result may be
+/// negative (through-zero linear modulation), and is limited to the Nyquist
+/// frequency in either direction.
+#[allow(clippy::cast_precision_loss)]
+pub fn frequency(base: Sample, pitch: &Vector, linear: &Vector) -> Vector {
+    let base = Vector::splat(base);
+    let nyquist = Vector::splat(SAMPLE_RATE as Sample / 2.);
+
+    (base * pitch.exp2() + base * linear).simd_clamp(-nyquist, nyquist)
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Accumulator
+
+/// A `Phase` accumulator, holding the phase (wrapped to [0, 1)) at the start of
+/// the next block. Wrapping the phase each block (rather than accumulating
+/// time) keeps full precision regardless of how long the accumulator runs.
+#[derive(new, Clone, Copy, Debug, Default)]
+pub struct Phase {
+    pub value: Sample,
+}
+
+impl Phase {
+    /// Advances the accumulator by a block of frames at the given per-frame
+    /// frequencies, returning the phase of each frame (before the increment
+    /// for that frame is applied) and the per-frame increments.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn advance(&mut self, frequency: &Vector) -> (Vector, Vector) {
+        let increment = frequency / Vector::splat(SAMPLE_RATE as Sample);
+        let total = scan(increment);
+        let phase = wrap(&(Vector::splat(self.value) + total - increment));
+
+        self.value = wrap_sample(self.value + total[BUFFER_FRAMES - 1]);
+
+        (phase, increment)
+    }
+
+    /// Advances the accumulator as for `advance`, resetting the phase to zero
+    /// at each frame where `reset` is set (hard sync). Also returns the phase
+    /// which would have been reached at each reset frame (or zero for frames
+    /// without a reset), for use in correcting discontinuities.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn advance_sync(
+        &mut self,
+        frequency: &Vector,
+        reset: &Mask<i64, BUFFER_FRAMES>,
+    ) -> (Vector, Vector, Vector) {
+        if !reset.any() {
+            let (phase, increment) = self.advance(frequency);
+
+            return (phase, increment, Vector::splat(0.));
+        }
+
+        let increment = frequency / Vector::splat(SAMPLE_RATE as Sample);
+        let mut phase = Vector::splat(0.);
+        let mut previous = Vector::splat(0.);
+        let mut value = self.value;
+
+        for i in 0..BUFFER_FRAMES {
+            if reset.test(i) {
+                previous[i] = value;
+                value = 0.;
+            }
+
+            phase[i] = value;
+            value = wrap_sample(value + increment[i]);
+        }
+
+        self.value = value;
+
+        (phase, increment, previous)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Edge
+
+/// An `Edge` detector for sync (and trigger) inputs, tracking the last sample
+/// of the previous block so that edges spanning blocks are detected.
+#[derive(new, Clone, Copy, Debug, Default)]
+pub struct Edge {
+    #[new(default)]
+    previous: Sample,
+}
+
+impl Edge {
+    /// Gets a mask of the frames at which the input rises from zero (or below)
+    /// to above zero.
+    pub fn rising(&mut self, input: &Vector) -> Mask<i64, BUFFER_FRAMES> {
+        let zero = Vector::splat(0.);
+        let previous = input.shift_elements_right::<1>(self.previous);
+
+        self.previous = input[BUFFER_FRAMES - 1];
+
+        previous.simd_le(zero) & input.simd_gt(zero)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Functions
+
+/// Gets the inclusive prefix sum of a vector (a log-step scan).
+pub fn scan(mut vector: Vector) -> Vector {
+    vector += vector.shift_elements_right::<1>(0.);
+    vector += vector.shift_elements_right::<2>(0.);
+    vector += vector.shift_elements_right::<4>(0.);
+    vector += vector.shift_elements_right::<8>(0.);
+    vector += vector.shift_elements_right::<16>(0.);
+    vector += vector.shift_elements_right::<32>(0.);
+    vector
+}
+
+/// Wraps each value of a vector to [0, 1).
+pub fn wrap(vector: &Vector) -> Vector {
+    vector - vector.floor()
+}
+
+/// Wraps a value to [0, 1).
+pub fn wrap_sample(value: Sample) -> Sample {
+    value - value.floor()
+}
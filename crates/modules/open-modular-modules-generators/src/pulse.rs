@@ -0,0 +1,206 @@
+use std::{
+    marker::PhantomData,
+    simd::{
+        Select as _,
+        cmp::SimdPartialOrd as _,
+        num::SimdFloat as _,
+    },
+};
+
+use derive_more::with_trait::Debug;
+use fancy_constructor::new;
+use open_modular_core::{
+    Sample,
+    Vector,
+};
+use open_modular_engine::{
+    module::{
+        ModuleDefine,
+        ModuleDefinition,
+        ModuleDefinitionBuilder,
+        ModuleInstantiate,
+        ModuleState,
+        Version,
+        module,
+    },
+    port::{
+        Port,
+        PortInputs,
+        PortOutputVectorGet as _,
+        PortOutputs,
+    },
+    processor::{
+        Process,
+        ProcessArgs,
+    },
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    oscillator::{
+        self,
+        Oscillator,
+    },
+    phase::{
+        self,
+        FREQUENCY_C4,
+    },
+};
+
+// =================================================================================================
+// Pulse
+// =================================================================================================
+
+/// The limits of the pulse width, keeping both parts of the cycle audible.
+static WIDTH_MIN: Sample = 0.01;
+static WIDTH_MAX: Sample = 0.99;
+
+#[module(id = "f83a7f71-ecda-449b-b361-5527110f3941", state)]
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct Pulse<R>
+where
+    R: Debug,
+{
+    #[new(default)]
+    oscillator: Oscillator,
+    #[new(default)]
+    state: PulseState,
+
+    port_inputs: PortInputs,
+    port_outputs: PortOutputs,
+    #[debug(skip)]
+    #[new(default)]
+    _r: PhantomData<R>,
+}
+
+impl<R> ModuleDefine for Pulse<R>
+where
+    R: Debug,
+{
+    fn define(module: ModuleDefinitionBuilder) -> impl Into<ModuleDefinition> {
+        let module = oscillator::with_inputs(module).with_input(|input| {
+            input
+                .name("PWM")
+                .description("Pulse width modulation (1V = full cycle, added to the width)")
+        });
+
+        oscillator::with_control_inputs(module)
+            .name("oscillator/pulse")
+            .version(Version::new(1, 1, 0))
+            .category("Oscillator")
+            .description("Band-Limited Pulse Oscillator")
+            .usage(oscillator::USAGE)
+            .author("Andrew Cherry (@kolektiv)")
+            .license("AGPL-3.0-only")
+            .with_tag("audio")
+            .with_tag("oscillator")
+            .with_output(|output| output.name("Output"))
+    }
+}
+
+impl<R> ModuleInstantiate for Pulse<R>
+where
+    R: Debug,
+{
+    type Context = R;
+
+    fn instantiate(
+        _context: Self::Context,
+        _config: Self::Config,
+        port_inputs: PortInputs,
+        port_outputs: PortOutputs,
+    ) -> Self {
+        Self::new(port_inputs, port_outputs)
+    }
+}
+
+impl<R> ModuleState for Pulse<R>
+where
+    R: Debug,
+{
+    type State = PulseState;
+
+    fn state(&self) -> Self::State {
+        PulseState {
+            phase: self.oscillator.phase(),
+            ..self.state
+        }
+    }
+
+    fn restore(&mut self, state: Self::State) {
+        self.oscillator.set_phase(state.phase);
+        self.state = state;
+    }
+}
+
+impl<R> Process for Pulse<R>
+where
+    R: Debug,
+{
+    fn process(&mut self, args: &ProcessArgs) {
+        if let Some(Port::Connected(output)) = self.port_outputs.vector(0, &args.token) {
+            let block = self.oscillator.advance(
+                &self.port_inputs,
+                &args.token,
+                self.state.frequency,
+                self.state.fine,
+                5,
+            );
+
+            let width =
+                oscillator::control(&self.port_inputs, &args.token, 4, self.state.width, 1.)
+                    .simd_clamp(Vector::splat(WIDTH_MIN), Vector::splat(WIDTH_MAX));
+
+            let amplitude =
+                oscillator::control(&self.port_inputs, &args.token, 6, self.state.amplitude, 1.);
+            let fall = phase::wrap(&(block.phase - width));
+
+            *output = (pulse(&block.phase, &width)
+                + oscillator::polyblep(&block.phase, &block.increment)
+                - oscillator::polyblep(&fall, &block.increment))
+                * amplitude;
+
+            block.correct_sync(output, |phase| pulse(phase, &width) * amplitude);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.oscillator.reset();
+    }
+}
+
+/// The naive (aliasing) pulse, high (1) for the first part of the cycle, given
+/// by the width, and low (-1) for the remainder.
+fn pulse(phase: &Vector, width: &Vector) -> Vector {
+    phase
+        .simd_lt(*width)
+        .select(Vector::splat(1.), Vector::splat(-1.))
+}
+
+// State
+
+/// The state of a pulse oscillator, including the base values of the controls
+/// (see `OscillatorState`).
+#[derive(new, Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct PulseState {
+    /// The amplitude (peak) of the output
+    amplitude: Sample,
+    /// The fine tuning, in cents
+    fine: Sample,
+    /// The base frequency (at a pitch of 0V), in Hz
+    frequency: Sample,
+    /// The phase at the start of the next block, in [0, 1)
+    phase: Sample,
+    /// The pulse width (the high part of the cycle), in [0.01, 0.99]
+    width: Sample,
+}
+
+impl Default for PulseState {
+    fn default() -> Self {
+        Self::new(1., 0., FREQUENCY_C4, 0., 0.5)
+    }
+}
@@ -0,0 +1,93 @@
+use std::{
+    array,
+    simd::{
+        Simd,
+        num::SimdUint as _,
+    },
+    sync::atomic::{
+        AtomicU64,
+        Ordering,
+    },
+};
+
+use derive_more::with_trait::Debug;
+use fancy_constructor::new;
+use open_modular_core::{
+    BUFFER_FRAMES,
+    Sample,
+    Vector,
+};
+
+// =================================================================================================
+// Random
+// =================================================================================================
+
+/// A vector of random bits (or counters), one value per frame.
+pub type Bits = Simd<u64, BUFFER_FRAMES>;
+
+/// A counter-based random number generator, hashing a seed, a stream, and a
+/// counter (with the `SplitMix64` finalizer) to produce each value. As every
+/// value depends only on those three inputs, any number of independent streams
+/// can be drawn from one seed, and generation is reproducible for a given seed
+/// regardless of which streams are used (or in which order).
+#[derive(new, Clone, Copy, Debug)]
+pub struct Random {
+    seed: u64,
+}
+
+impl Random {
+    /// Gets a new seed, distinct for every call within a process (so that
+    /// modules instantiated without an explicit seed are not correlated).
+    pub fn seed() -> u64 {
+        static SEED: AtomicU64 = AtomicU64::new(0);
+
+        mix(SEED.fetch_add(1, Ordering::Relaxed).wrapping_add(0x5eed))
+    }
+
+    /// Gets random bits for each counter value in the given stream.
+    pub fn bits(self, stream: u64, counter: &Bits) -> Bits {
+        let key = mix(self.seed ^ mix(stream));
+        let mut z = counter * Bits::splat(GOLDEN) + Bits::splat(key);
+
+        z = (z ^ (z >> Bits::splat(30))) * Bits::splat(MIX_1);
+        z = (z ^ (z >> Bits::splat(27))) * Bits::splat(MIX_2);
+        z ^ (z >> Bits::splat(31))
+    }
+
+    /// Gets a uniform random value in [0, 1) for each counter value in the
+    /// given stream.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn unipolar(self, stream: u64, counter: &Bits) -> Vector {
+        (self.bits(stream, counter) >> Bits::splat(11)).cast::<Sample>()
+            * Vector::splat(1. / (1_u64 << 53) as Sample)
+    }
+
+    /// Gets a uniform random value in [-1, 1) for each counter value in the
+    /// given stream.
+    pub fn bipolar(self, stream: u64, counter: &Bits) -> Vector {
+        self.unipolar(stream, counter) * Vector::splat(2.) - Vector::splat(1.)
+    }
+}
+
+/// Gets the counter for each frame of a block, given the counter of the first
+/// frame.
+pub fn counter(start: u64) -> Bits {
+    Bits::splat(start) + Bits::from_array(array::from_fn(|i| i as u64))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Functions
+
+static GOLDEN: u64 = 0x9e37_79b9_7f4a_7c15;
+static MIX_1: u64 = 0xbf58_476d_1ce4_e5b9;
+static MIX_2: u64 = 0x94d0_49bb_1331_11eb;
+
+/// The scalar `SplitMix64` finalizer, used to derive keys and seeds.
+fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(GOLDEN);
+
+    z = (z ^ (z >> 30)).wrapping_mul(MIX_1);
+    z = (z ^ (z >> 27)).wrapping_mul(MIX_2);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,143 @@
+use std::marker::PhantomData;
+
+use derive_more::with_trait::Debug;
+use fancy_constructor::new;
+use open_modular_core::Vector;
+use open_modular_engine::{
+    module::{
+        ModuleDefine,
+        ModuleDefinition,
+        ModuleDefinitionBuilder,
+        ModuleInstantiate,
+        ModuleState,
+        Version,
+        module,
+    },
+    port::{
+        Port,
+        PortInputs,
+        PortOutputVectorGet as _,
+        PortOutputs,
+    },
+    processor::{
+        Process,
+        ProcessArgs,
+    },
+};
+
+use crate::oscillator::{
+    self,
+    Oscillator,
+    OscillatorState,
+};
+
+// =================================================================================================
+// Saw
+// =================================================================================================
+
+#[module(id = "a4e53076-b8b0-4d0d-b73f-5bcb9c55e888", state)]
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct Saw<R>
+where
+    R: Debug,
+{
+    #[new(default)]
+    oscillator: Oscillator,
+    #[new(default)]
+    state: OscillatorState,
+
+    port_inputs: PortInputs,
+    port_outputs: PortOutputs,
+    #[debug(skip)]
+    #[new(default)]
+    _r: PhantomData<R>,
+}
+
+impl<R> ModuleDefine for Saw<R>
+where
+    R: Debug,
+{
+    fn define(module: ModuleDefinitionBuilder) -> impl Into<ModuleDefinition> {
+        oscillator::with_control_inputs(oscillator::with_inputs(module))
+            .name("oscillator/saw")
+            .version(Version::new(1, 1, 0))
+            .category("Oscillator")
+            .description("Band-Limited Sawtooth Oscillator")
+            .usage(oscillator::USAGE)
+            .author("Andrew Cherry (@kolektiv)")
+            .license("AGPL-3.0-only")
+            .with_tag("audio")
+            .with_tag("oscillator")
+            .with_output(|output| output.name("Output"))
+    }
+}
+
+impl<R> ModuleInstantiate for Saw<R>
+where
+    R: Debug,
+{
+    type Context = R;
+
+    fn instantiate(
+        _context: Self::Context,
+        _config: Self::Config,
+        port_inputs: PortInputs,
+        port_outputs: PortOutputs,
+    ) -> Self {
+        Self::new(port_inputs, port_outputs)
+    }
+}
+
+impl<R> ModuleState for Saw<R>
+where
+    R: Debug,
+{
+    type State = OscillatorState;
+
+    fn state(&self) -> Self::State {
+        OscillatorState {
+            phase: self.oscillator.phase(),
+            ..self.state
+        }
+    }
+
+    fn restore(&mut self, state: Self::State) {
+        self.oscillator.set_phase(state.phase);
+        self.state = state;
+    }
+}
+
+impl<R> Process for Saw<R>
+where
+    R: Debug,
+{
+    fn process(&mut self, args: &ProcessArgs) {
+        if let Some(Port::Connected(output)) = self.port_outputs.vector(0, &args.token) {
+            let block = self.oscillator.advance(
+                &self.port_inputs,
+                &args.token,
+                self.state.frequency,
+                self.state.fine,
+                4,
+            );
+
+            let amplitude =
+                oscillator::control(&self.port_inputs, &args.token, 5, self.state.amplitude, 1.);
+
+            *output = (saw(&block.phase) - oscillator::polyblep(&block.phase, &block.increment))
+                * amplitude;
+
+            block.correct_sync(output, |phase| saw(phase) * amplitude);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.oscillator.reset();
+    }
+}
+
+/// The naive (aliasing) rising sawtooth, from -1 to 1.
+fn saw(phase: &Vector) -> Vector {
+    phase * Vector::splat(2.) - Vector::splat(1.)
+}
@@ -0,0 +1,196 @@
+use std::{
+    f64::consts::TAU,
+    marker::PhantomData,
+    simd::StdFloat as _,
+};
+
+use derive_more::with_trait::Debug;
+use fancy_constructor::new;
+use open_modular_core::{
+    BUFFER_FRAMES,
+    SAMPLE_RATE,
+    Sample,
+    Vector,
+};
+use open_modular_engine::{
+    module::{
+        ModuleDefine,
+        ModuleDefinition,
+        ModuleDefinitionBuilder,
+        ModuleInstantiate,
+        ModuleMigrate,
+        ModuleState,
+        Version,
+        module,
+    },
+    port::{
+        Port,
+        PortInputs,
+        PortOutputVectorGet as _,
+        PortOutputs,
+    },
+    processor::{
+        Process,
+        ProcessArgs,
+    },
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    oscillator::{
+        self,
+        Oscillator,
+        OscillatorState,
+    },
+    phase,
+};
+
+// =================================================================================================
+// Sine
+// =================================================================================================
+
+#[module(id = "f75487a4-7847-43f9-ab47-71bd6acfb78d", migrate, state)]
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct Sine<R>
+where
+    R: Debug,
+{
+    #[new(default)]
+    oscillator: Oscillator,
+    #[new(default)]
+    state: OscillatorState,
+
+    port_inputs: PortInputs,
+    port_outputs: PortOutputs,
+    #[debug(skip)]
+    #[new(default)]
+    _r: PhantomData<R>,
+}
+
+impl<R> ModuleDefine for Sine<R>
+where
+    R: Debug,
+{
+    fn define(module: ModuleDefinitionBuilder) -> impl Into<ModuleDefinition> {
+        oscillator::with_control_inputs(oscillator::with_inputs(module))
+            .name("oscillator/sine")
+            .version(Version::new(2, 1, 0))
+            .category("Oscillator")
+            .description("Sinusoidal Oscillator")
+            .usage(oscillator::USAGE)
+            .author("Andrew Cherry (@kolektiv)")
+            .license("AGPL-3.0-only")
+            .with_tag("audio")
+            .with_tag("oscillator")
+            .with_output(|output| output.name("Output"))
+    }
+}
+
+impl<R> ModuleInstantiate for Sine<R>
+where
+    R: Debug,
+{
+    type Context = R;
+
+    fn instantiate(
+        _context: Self::Context,
+        _config: Self::Config,
+        port_inputs: PortInputs,
+        port_outputs: PortOutputs,
+    ) -> Self {
+        Self::new(port_inputs, port_outputs)
+    }
+}
+
+impl<R> ModuleMigrate for Sine<R>
+where
+    R: Debug,
+{
+    fn migrates(version: &Version) -> bool {
+        version.major == 1
+    }
+
+    /// Migrates the version 1 state (the time of each frame of the last block
+    /// processed, at a fixed frequency of 440Hz and amplitude of 0.15) to the
+    /// equivalent phase and controls. The migrated amplitude remains 0.15, so
+    /// that a migrated instance sounds as it did, although new instances now
+    /// default to full scale (see `OscillatorState`). Where no state was
+    /// stored, the controls are migrated at a phase of 0.
+    #[allow(clippy::cast_precision_loss)]
+    fn migrate_state(_version: &Version, state: Option<Value>) -> Option<Value> {
+        let phase = match state {
+            Some(state) => {
+                let state = serde_json::from_value::<SineStateV1>(state).ok()?;
+                let time = state.time.get(BUFFER_FRAMES - 1)? + 1. / SAMPLE_RATE as Sample;
+
+                phase::wrap_sample(time * 440.)
+            }
+            None => 0.,
+        };
+
+        let state = OscillatorState::new(0.15, 0., 440., phase);
+
+        serde_json::to_value(state).ok()
+    }
+}
+
+impl<R> ModuleState for Sine<R>
+where
+    R: Debug,
+{
+    type State = OscillatorState;
+
+    fn state(&self) -> Self::State {
+        OscillatorState {
+            phase: self.oscillator.phase(),
+            ..self.state
+        }
+    }
+
+    fn restore(&mut self, state: Self::State) {
+        self.oscillator.set_phase(state.phase);
+        self.state = state;
+    }
+}
+
+impl<R> Process for Sine<R>
+where
+    R: Debug,
+{
+    fn process(&mut self, args: &ProcessArgs) {
+        if let Some(Port::Connected(output)) = self.port_outputs.vector(0, &args.token) {
+            let block = self.oscillator.advance(
+                &self.port_inputs,
+                &args.token,
+                self.state.frequency,
+                self.state.fine,
+                4,
+            );
+
+            let amplitude =
+                oscillator::control(&self.port_inputs, &args.token, 5, self.state.amplitude, 1.);
+
+            *output = sine(&block.phase) * amplitude;
+
+            block.correct_sync(output, |phase| sine(phase) * amplitude);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.oscillator.reset();
+    }
+}
+
+fn sine(phase: &Vector) -> Vector {
+    (phase * Vector::splat(TAU)).sin()
+}
+
+// State
+
+/// The version 1 state of a sine oscillator (see `migrate_state`).
+#[derive(Debug, Deserialize)]
+struct SineStateV1 {
+    time: Vec<Sample>,
+}
@@ -0,0 +1,284 @@
+use std::{
+    f64::consts::TAU,
+    path::{
+        Path,
+        PathBuf,
+    },
+    simd::{
+        Simd,
+        StdFloat as _,
+        cmp::SimdOrd as _,
+        num::SimdFloat as _,
+    },
+};
+
+use derive_more::with_trait::Debug;
+use fancy_constructor::new;
+use hound::{
+    SampleFormat,
+    WavReader,
+};
+use open_modular_core::{
+    BUFFER_FRAMES,
+    Sample,
+    Vector,
+};
+use rustfft::{
+    FftPlanner,
+    num_complex::Complex,
+};
+use snafu::{
+    ResultExt as _,
+    Snafu,
+    ensure,
+};
+
+// =================================================================================================
+// Table
+// =================================================================================================
+
+/// The number of samples in a single frame (cycle) of a wavetable. Wavetable
+/// files with a length which is a multiple of this are treated as a sequence
+/// of frames, and any other file is treated as a single cycle (and resampled).
+pub static FRAME_SAMPLES: usize = 2048;
+
+/// A `Table` is a wavetable of one or more single-cycle frames, held as a
+/// mipmap of band-limited levels. Each level holds half the harmonics of the
+/// previous level (so that each level is alias-free for an octave of
+/// frequency), at a length of four samples per harmonic (so that linear
+/// interpolation remains accurate at the highest harmonic).
+#[derive(Debug)]
+pub struct Table {
+    frames: usize,
+    #[debug(skip)]
+    levels: Vec<TableLevel>,
+}
+
+impl Table {
+    /// Loads a wavetable from a WAV file (integer or floating point, using the
+    /// first channel where the file has more than one).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can not be read, or is empty.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TableError> {
+        let path = path.as_ref();
+        let mut reader = WavReader::open(path).context(ReadError { path })?;
+        let spec = reader.spec();
+
+        let samples = match spec.sample_format {
+            SampleFormat::Float => reader
+                .samples::<f32>()
+                .map(|sample| sample.map(Sample::from))
+                .collect::<Result<Vec<_>, _>>(),
+            SampleFormat::Int => {
+                let scale = Sample::from(2_u32).powi(i32::from(spec.bits_per_sample) - 1);
+
+                reader
+                    .samples::<i32>()
+                    .map(|sample| sample.map(|sample| Sample::from(sample) / scale))
+                    .collect::<Result<Vec<_>, _>>()
+            }
+        }
+        .context(ReadError { path })?;
+
+        let samples = samples
+            .into_iter()
+            .step_by(usize::from(spec.channels.max(1)))
+            .collect::<Vec<_>>();
+
+        Self::from_samples(&samples)
+    }
+
+    /// Creates a wavetable from raw samples, either a sequence of frames (where
+    /// the length is a multiple of `FRAME_SAMPLES`) or a single cycle.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there are no samples.
+    pub fn from_samples(samples: &[Sample]) -> Result<Self, TableError> {
+        ensure!(!samples.is_empty(), EmptyError);
+
+        let frames = if samples.len().is_multiple_of(FRAME_SAMPLES) {
+            samples.chunks(FRAME_SAMPLES).map(<[_]>::to_vec).collect()
+        } else {
+            vec![resample(samples)]
+        };
+
+        Ok(Self::from_frames(&frames))
+    }
+
+    fn from_frames(frames: &[Vec<Sample>]) -> Self {
+        let mut planner = FftPlanner::new();
+        let forward = planner.plan_fft_forward(FRAME_SAMPLES);
+
+        let spectra = frames
+            .iter()
+            .map(|frame| {
+                let mut spectrum = frame
+                    .iter()
+                    .map(|sample| Complex::new(*sample, 0.))
+                    .collect::<Vec<_>>();
+
+                forward.process(&mut spectrum);
+                spectrum
+            })
+            .collect::<Vec<_>>();
+
+        let levels = (0..FRAME_SAMPLES.ilog2())
+            .map(|level| (FRAME_SAMPLES / 2) >> level)
+            .map(|harmonics| TableLevel::from_spectra(&mut planner, &spectra, harmonics))
+            .collect();
+
+        Self {
+            frames: frames.len(),
+            levels,
+        }
+    }
+}
+
+impl Table {
+    /// Gets the number of frames in the table.
+    #[must_use]
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// Gets the level of the mipmap to use for the given (absolute) per-frame
+    /// phase increment, being the most detailed level without harmonics above
+    /// the Nyquist frequency.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    #[allow(clippy::cast_sign_loss)]
+    #[must_use]
+    pub fn level(&self, increment: Sample) -> usize {
+        let level = (FRAME_SAMPLES as Sample * increment).log2().ceil().max(0.) as usize;
+
+        level.min(self.levels.len() - 1)
+    }
+
+    /// Reads the table at the given level for each frame of a block, given the
+    /// phase (in [0, 1)) and position (in [0, 1], interpolating between
+    /// adjacent frames of the table).
+    #[allow(clippy::cast_precision_loss)]
+    #[must_use]
+    pub fn read(&self, level: usize, phase: &Vector, position: &Vector) -> Vector {
+        let level = &self.levels[level];
+        let length = Simd::<usize, BUFFER_FRAMES>::splat(level.length);
+
+        let index = phase * Vector::splat(level.length as Sample);
+        let index_floor = index.floor();
+        let index_fraction = index - index_floor;
+        let index_0 = index_floor.cast::<usize>() % length;
+        let index_1 = (index_0 + Simd::splat(1)) % length;
+
+        let last = self.frames - 1;
+        let frame = position.simd_clamp(Vector::splat(0.), Vector::splat(1.))
+            * Vector::splat(last as Sample);
+        let frame_floor = frame.floor();
+        let frame_fraction = frame - frame_floor;
+        let frame_0 = frame_floor.cast::<usize>();
+        let frame_1 = (frame_0 + Simd::splat(1)).simd_min(Simd::splat(last));
+
+        let read = |frame: Simd<usize, BUFFER_FRAMES>| {
+            let a = Vector::gather_or_default(&level.samples, frame * length + index_0);
+            let b = Vector::gather_or_default(&level.samples, frame * length + index_1);
+
+            a + (b - a) * index_fraction
+        };
+
+        let a = read(frame_0);
+        let b = read(frame_1);
+
+        a + (b - a) * frame_fraction
+    }
+}
+
+impl Default for Table {
+    /// A table of a single sine frame.
+    #[allow(clippy::cast_precision_loss)]
+    fn default() -> Self {
+        let frame = (0..FRAME_SAMPLES)
+            .map(|i| (TAU * i as Sample / FRAME_SAMPLES as Sample).sin())
+            .collect();
+
+        Self::from_frames(&[frame])
+    }
+}
+
+// Level
+
+/// A `TableLevel` holds every frame of a wavetable (contiguously) limited to
+/// the given number of harmonics.
+#[derive(new, Debug)]
+struct TableLevel {
+    length: usize,
+    samples: Vec<Sample>,
+}
+
+impl TableLevel {
+    #[allow(clippy::cast_precision_loss)]
+    fn from_spectra(
+        planner: &mut FftPlanner<Sample>,
+        spectra: &[Vec<Complex<Sample>>],
+        harmonics: usize,
+    ) -> Self {
+        let harmonics = harmonics.min(FRAME_SAMPLES / 2 - 1);
+        let length = (harmonics * 4).max(64);
+        let inverse = planner.plan_fft_inverse(length);
+        let scale = 1. / FRAME_SAMPLES as Sample;
+
+        let mut samples = Vec::with_capacity(spectra.len() * length);
+
+        for spectrum in spectra {
+            let mut level = vec![Complex::new(0., 0.); length];
+
+            level[0] = spectrum[0];
+
+            for harmonic in 1..=harmonics {
+                level[harmonic] = spectrum[harmonic];
+                level[length - harmonic] = spectrum[FRAME_SAMPLES - harmonic];
+            }
+
+            inverse.process(&mut level);
+            samples.extend(level.iter().map(|sample| sample.re * scale));
+        }
+
+        Self::new(length, samples)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Functions
+
+/// Resamples a single cycle of any length to `FRAME_SAMPLES` (using linear
+/// interpolation, wrapping at the end of the cycle).
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+#[allow(clippy::cast_sign_loss)]
+fn resample(samples: &[Sample]) -> Vec<Sample> {
+    let ratio = samples.len() as Sample / FRAME_SAMPLES as Sample;
+
+    (0..FRAME_SAMPLES)
+        .map(|i| {
+            let index = i as Sample * ratio;
+            let fraction = index.fract();
+            let a = samples[index as usize % samples.len()];
+            let b = samples[(index as usize + 1) % samples.len()];
+
+            a + (b - a) * fraction
+        })
+        .collect()
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Error
+
+#[derive(Debug, Snafu)]
+#[snafu(context(suffix(Error)), visibility(pub(crate)))]
+pub enum TableError {
+    #[snafu(display("wavetable is empty"))]
+    Empty,
+    #[snafu(display("wavetable {} could not be read", path.display()))]
+    Read { path: PathBuf, source: hound::Error },
+}
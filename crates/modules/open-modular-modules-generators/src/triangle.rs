@@ -0,0 +1,157 @@
+use std::{
+    marker::PhantomData,
+    simd::num::SimdFloat as _,
+};
+
+use derive_more::with_trait::Debug;
+use fancy_constructor::new;
+use open_modular_core::Vector;
+use open_modular_engine::{
+    module::{
+        ModuleDefine,
+        ModuleDefinition,
+        ModuleDefinitionBuilder,
+        ModuleInstantiate,
+        ModuleState,
+        Version,
+        module,
+    },
+    port::{
+        Port,
+        PortInputs,
+        PortOutputVectorGet as _,
+        PortOutputs,
+    },
+    processor::{
+        Process,
+        ProcessArgs,
+    },
+};
+
+use crate::{
+    oscillator::{
+        self,
+        Oscillator,
+        OscillatorState,
+    },
+    phase,
+};
+
+// =================================================================================================
+// Triangle
+// =================================================================================================
+
+#[module(id = "c9252544-f792-4a99-9044-74f07f9b4ee6", state)]
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct Triangle<R>
+where
+    R: Debug,
+{
+    #[new(default)]
+    oscillator: Oscillator,
+    #[new(default)]
+    state: OscillatorState,
+
+    port_inputs: PortInputs,
+    port_outputs: PortOutputs,
+    #[debug(skip)]
+    #[new(default)]
+    _r: PhantomData<R>,
+}
+
+impl<R> ModuleDefine for Triangle<R>
+where
+    R: Debug,
+{
+    fn define(module: ModuleDefinitionBuilder) -> impl Into<ModuleDefinition> {
+        oscillator::with_control_inputs(oscillator::with_inputs(module))
+            .name("oscillator/triangle")
+            .version(Version::new(1, 1, 0))
+            .category("Oscillator")
+            .description("Band-Limited Triangle Oscillator")
+            .usage(oscillator::USAGE)
+            .author("Andrew Cherry (@kolektiv)")
+            .license("AGPL-3.0-only")
+            .with_tag("audio")
+            .with_tag("oscillator")
+            .with_output(|output| output.name("Output"))
+    }
+}
+
+impl<R> ModuleInstantiate for Triangle<R>
+where
+    R: Debug,
+{
+    type Context = R;
+
+    fn instantiate(
+        _context: Self::Context,
+        _config: Self::Config,
+        port_inputs: PortInputs,
+        port_outputs: PortOutputs,
+    ) -> Self {
+        Self::new(port_inputs, port_outputs)
+    }
+}
+
+impl<R> ModuleState for Triangle<R>
+where
+    R: Debug,
+{
+    type State = OscillatorState;
+
+    fn state(&self) -> Self::State {
+        OscillatorState {
+            phase: self.oscillator.phase(),
+            ..self.state
+        }
+    }
+
+    fn restore(&mut self, state: Self::State) {
+        self.oscillator.set_phase(state.phase);
+        self.state = state;
+    }
+}
+
+impl<R> Process for Triangle<R>
+where
+    R: Debug,
+{
+    fn process(&mut self, args: &ProcessArgs) {
+        if let Some(Port::Connected(output)) = self.port_outputs.vector(0, &args.token) {
+            let block = self.oscillator.advance(
+                &self.port_inputs,
+                &args.token,
+                self.state.frequency,
+                self.state.fine,
+                4,
+            );
+
+            // The slope changes by eight per cycle at each corner, and the
+            // PolyBLAMP residual is for a change of two per frame.
+            let scale = block.increment.abs() * Vector::splat(4.);
+            let amplitude =
+                oscillator::control(&self.port_inputs, &args.token, 5, self.state.amplitude, 1.);
+            let peak = phase::wrap(&(block.phase - Vector::splat(0.5)));
+
+            *output = (triangle(&block.phase)
+                + scale
+                    * (oscillator::polyblamp(&block.phase, &block.increment)
+                        - oscillator::polyblamp(&peak, &block.increment)))
+                * amplitude;
+
+            block.correct_sync(output, |phase| triangle(phase) * amplitude);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.oscillator.reset();
+    }
+}
+
+/// The naive (aliasing) triangle, rising from -1 at the start of the cycle to
+/// 1 at the midpoint.
+fn triangle(phase: &Vector) -> Vector {
+    Vector::splat(1.) - (phase - Vector::splat(0.5)).abs() * Vector::splat(4.)
+}
@@ -0,0 +1,309 @@
+use std::{
+    collections::HashMap,
+    marker::PhantomData,
+    path::{
+        Path,
+        PathBuf,
+    },
+    simd::num::SimdFloat as _,
+    sync::{
+        Arc,
+        LazyLock,
+        Mutex,
+    },
+};
+
+use derive_more::with_trait::Debug;
+use fancy_constructor::new;
+use open_modular_core::Sample;
+use open_modular_engine::{
+    module::{
+        ModuleDefine,
+        ModuleDefinition,
+        ModuleDefinitionBuilder,
+        ModuleInstantiate,
+        ModuleState,
+        Version,
+        module,
+    },
+    port::{
+        Port,
+        PortInputs,
+        PortOutputVectorGet as _,
+        PortOutputs,
+    },
+    processor::{
+        Process,
+        ProcessArgs,
+    },
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    oscillator::{
+        self,
+        Oscillator,
+    },
+    phase::FREQUENCY_C4,
+    table::{
+        Table,
+        TableError,
+    },
+};
+
+// =================================================================================================
+// Wavetable
+// =================================================================================================
+
+/// The tables loaded from wavetable files, by path. Tables are retained for the
+/// lifetime of the process, so that a table loaded when a configuration is
+/// deserialized (see `WavetableConfig`) is never loaded again.
+static TABLES: LazyLock<Mutex<HashMap<PathBuf, Arc<Table>>>> = LazyLock::new(Mutex::default);
+
+/// The table of a single sine frame, used where no wavetable file is given.
+static TABLE_SINE: LazyLock<Arc<Table>> = LazyLock::new(Arc::default);
+
+// -------------------------------------------------------------------------------------------------
+
+// Oscillator
+
+#[module(id = "0b6d1f38-6a43-4c5e-9a4e-2f7d8c91b3a5", state)]
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct Wavetable<R>
+where
+    R: Debug,
+{
+    #[new(default)]
+    oscillator: Oscillator,
+    #[new(default)]
+    state: WavetableState,
+    table: Arc<Table>,
+
+    port_inputs: PortInputs,
+    port_outputs: PortOutputs,
+    #[debug(skip)]
+    #[new(default)]
+    _r: PhantomData<R>,
+}
+
+impl<R> ModuleDefine for Wavetable<R>
+where
+    R: Debug,
+{
+    fn define(module: ModuleDefinitionBuilder) -> impl Into<ModuleDefinition> {
+        let module = oscillator::with_inputs(module).with_input(|input| {
+            input
+                .name("Position")
+                .description("Frame position (1V = full table, added to the position)")
+        });
+
+        oscillator::with_control_inputs(module)
+            .name("oscillator/wavetable")
+            .version(Version::new(1, 1, 0))
+            .category("Oscillator")
+            .description("Mipmapped Wavetable Oscillator")
+            .usage(oscillator::USAGE)
+            .author("Andrew Cherry (@kolektiv)")
+            .license("AGPL-3.0-only")
+            .with_tag("audio")
+            .with_tag("oscillator")
+            .with_output(|output| output.name("Output"))
+    }
+}
+
+impl<R> ModuleInstantiate for Wavetable<R>
+where
+    R: Debug,
+{
+    type Config = WavetableConfig;
+    type Context = R;
+
+    /// Instantiates the oscillator with the table of the configuration (which
+    /// was loaded when the configuration was deserialized).
+    fn instantiate(
+        _context: Self::Context,
+        config: Self::Config,
+        port_inputs: PortInputs,
+        port_outputs: PortOutputs,
+    ) -> Self {
+        Self::new(config.table, port_inputs, port_outputs)
+    }
+}
+
+impl<R> ModuleState for Wavetable<R>
+where
+    R: Debug,
+{
+    type State = WavetableState;
+
+    fn state(&self) -> Self::State {
+        WavetableState {
+            phase: self.oscillator.phase(),
+            ..self.state
+        }
+    }
+
+    fn restore(&mut self, state: Self::State) {
+        self.oscillator.set_phase(state.phase);
+        self.state = state;
+    }
+}
+
+impl<R> Process for Wavetable<R>
+where
+    R: Debug,
+{
+    fn process(&mut self, args: &ProcessArgs) {
+        if let Some(Port::Connected(output)) = self.port_outputs.vector(0, &args.token) {
+            let block = self.oscillator.advance(
+                &self.port_inputs,
+                &args.token,
+                self.state.frequency,
+                self.state.fine,
+                5,
+            );
+
+            let position =
+                oscillator::control(&self.port_inputs, &args.token, 4, self.state.position, 1.);
+            let amplitude =
+                oscillator::control(&self.port_inputs, &args.token, 6, self.state.amplitude, 1.);
+            let level = self.table.level(block.increment.abs().reduce_max());
+
+            *output = self.table.read(level, &block.phase, &position) * amplitude;
+
+            block.correct_sync(output, |phase| {
+                self.table.read(level, phase, &position) * amplitude
+            });
+        }
+    }
+
+    fn reset(&mut self) {
+        self.oscillator.reset();
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Config
+
+/// The configuration of a wavetable oscillator (the path of the wavetable file
+/// to use, see `Table::load` - where no path is given, the oscillator uses a
+/// single sine frame).
+///
+/// The table is loaded, and the mipmap built, when the configuration is
+/// deserialized, which happens before the configuration is sent to the engine
+/// (see `ModuleConfig`), when a patch is loaded or an addressed instance is
+/// resolved (where a file can not be loaded, the configuration is invalid, and
+/// loading fails with an error). The engine only receives the deserialized
+/// configuration, so never reads a file or builds a mipmap on the engine
+/// thread. Loaded tables are cached by path, so that configurations sharing a
+/// file share a single table.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(into = "WavetableConfigPath", try_from = "WavetableConfigPath")]
+pub struct WavetableConfig {
+    path: Option<PathBuf>,
+    #[debug(skip)]
+    table: Arc<Table>,
+}
+
+impl WavetableConfig {
+    /// Creates a configuration for the wavetable file at the given path,
+    /// loading the table (if not already loaded).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can not be loaded.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, TableError> {
+        let path = path.into();
+        let table = table(&path)?;
+
+        Ok(Self {
+            path: Some(path),
+            table,
+        })
+    }
+}
+
+impl Default for WavetableConfig {
+    /// A default configuration using a single sine frame.
+    fn default() -> Self {
+        Self {
+            path: None,
+            table: Arc::clone(&TABLE_SINE),
+        }
+    }
+}
+
+impl From<WavetableConfig> for WavetableConfigPath {
+    fn from(config: WavetableConfig) -> Self {
+        Self { path: config.path }
+    }
+}
+
+impl TryFrom<WavetableConfigPath> for WavetableConfig {
+    type Error = TableError;
+
+    fn try_from(config: WavetableConfigPath) -> Result<Self, Self::Error> {
+        match config.path {
+            Some(path) => Self::load(path),
+            None => Ok(Self::default()),
+        }
+    }
+}
+
+/// The serialized form of a `WavetableConfig` (the path alone).
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+struct WavetableConfigPath {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<PathBuf>,
+}
+
+/// Gets the table loaded from the wavetable file at the given path, loading the
+/// table if it has not been loaded (the cache is not locked while loading).
+fn table(path: &Path) -> Result<Arc<Table>, TableError> {
+    if let Some(table) = TABLES.lock().expect("tables to lock").get(path) {
+        return Ok(Arc::clone(table));
+    }
+
+    let table = Arc::new(Table::load(path)?);
+
+    Ok(Arc::clone(
+        TABLES
+            .lock()
+            .expect("tables to lock")
+            .entry(path.to_owned())
+            .or_insert(table),
+    ))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// State
+
+/// The state of a wavetable oscillator, including the base values of the
+/// controls (see `OscillatorState` - the position is also modulated by the
+/// Position input).
+#[derive(new, Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct WavetableState {
+    /// The amplitude (peak) of the output
+    amplitude: Sample,
+    /// The fine tuning, in cents
+    fine: Sample,
+    /// The base frequency (at a pitch of 0V), in Hz
+    frequency: Sample,
+    /// The phase at the start of the next block, in [0, 1)
+    phase: Sample,
+    /// The position within the frames of the table, in [0, 1]
+    position: Sample,
+}
+
+impl Default for WavetableState {
+    fn default() -> Self {
+        Self::new(1., 0., FREQUENCY_C4, 0., 0.)
+    }
+}
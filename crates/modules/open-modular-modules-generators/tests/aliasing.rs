@@ -0,0 +1,153 @@
+use std::fmt::Debug;
+
+use open_modular_core::{
+    BUFFER_FRAMES,
+    SAMPLE_RATE,
+    Sample,
+};
+use open_modular_engine::module::{
+    ModuleIdentify as _,
+    module_enum,
+};
+use open_modular_modules_generators::{
+    Pulse,
+    Saw,
+    Triangle,
+};
+use open_modular_testing::{
+    Harness,
+    Record,
+    Recorder,
+    Recording,
+    magnitudes,
+};
+use serde_json::{
+    Value,
+    json,
+};
+use uuid::Uuid;
+
+// =================================================================================================
+// Aliasing
+// =================================================================================================
+
+/// The frequency of the rendered note (a high note, where the harmonics of a
+/// naive oscillator alias strongly).
+static FREQUENCY: Sample = 5000.;
+
+/// The number of frames analysed, chosen so that the note completes a whole
+/// number of cycles (and every harmonic, and every alias, falls exactly on a
+/// bin, so that no window is needed).
+static FRAMES: usize = 4800;
+
+/// The number of frames rendered (and discarded) before those analysed.
+static WARMUP: usize = 1024;
+
+/// The upper limit of the analysed band, in Hz (the audible band - aliases
+/// folded to just below the Nyquist frequency are inaudible, and are the least
+/// attenuated by polynomial band-limiting).
+static BAND: Sample = 20000.;
+
+/// The highest permitted alias level for each shape, in dB relative to the
+/// fundamental (the strongest audible alias of a naive saw or pulse at the
+/// test frequency is approximately -16dB, and of a naive triangle -34dB). The
+/// band-limited saw measures approximately -28dB, so the threshold is within
+/// a couple of dB of the correction, and a naive saw fails by more than 10dB.
+static THRESHOLD_SAW: Sample = -27.;
+static THRESHOLD_PULSE: Sample = -30.;
+static THRESHOLD_TRIANGLE: Sample = -45.;
+
+/// Renders the given oscillator at the test frequency (with the given
+/// additional state), returning the analysed frames.
+fn render(id: &Uuid, state: &Value) -> Vec<Sample> {
+    let mut harness = Harness::<Module<Recording>>::default();
+
+    let oscillator = harness.add(id);
+    let recorder = harness.add(&Recorder::<Recording>::id());
+
+    let mut snapshot = json!({
+        "amplitude": 1.,
+        "fine": 0.,
+        "frequency": FREQUENCY,
+        "phase": 0.,
+    });
+
+    snapshot
+        .as_object_mut()
+        .expect("snapshot to be an object")
+        .extend(state.as_object().cloned().unwrap_or_default());
+
+    harness.restore(&oscillator, snapshot);
+    harness.connect(recorder, 0, oscillator, 0);
+    harness.process((WARMUP + FRAMES).div_ceil(BUFFER_FRAMES));
+
+    harness.samples()[WARMUP..WARMUP + FRAMES].to_vec()
+}
+
+/// Gets the level (relative to the fundamental, in dB) of the strongest bin
+/// within the analysed band which is not a harmonic of the test frequency (any
+/// such energy is aliasing, as the note completes a whole number of cycles
+/// within the frames).
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+#[allow(clippy::cast_sign_loss)]
+fn alias_level(samples: &[Sample]) -> Sample {
+    let magnitudes = magnitudes(samples);
+
+    let spacing = SAMPLE_RATE as Sample / FRAMES as Sample;
+    let harmonic = (FREQUENCY / spacing).round() as usize;
+
+    // The DC bin is ignored, as the shapes are not required to be centred.
+
+    let band = (BAND / spacing) as usize;
+
+    let alias = (1..band)
+        .filter(|bin| bin % harmonic != 0)
+        .map(|bin| magnitudes[bin])
+        .fold(0., Sample::max);
+
+    20. * (alias / magnitudes[harmonic]).log10()
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Oscillators
+
+#[test]
+fn saw_aliasing() {
+    let level = alias_level(&render(&Saw::<Recording>::id(), &json!({})));
+
+    assert!(level < THRESHOLD_SAW, "saw alias level {level:.1}dB");
+}
+
+#[test]
+fn pulse_aliasing() {
+    let level = alias_level(&render(&Pulse::<Recording>::id(), &json!({ "width": 0.5 })));
+
+    assert!(level < THRESHOLD_PULSE, "pulse alias level {level:.1}dB");
+}
+
+#[test]
+fn triangle_aliasing() {
+    let level = alias_level(&render(&Triangle::<Recording>::id(), &json!({})));
+
+    assert!(
+        level < THRESHOLD_TRIANGLE,
+        "triangle alias level {level:.1}dB"
+    );
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Module
+
+#[module_enum(id = "9e3b7a15-4c2d-4f86-a0b9-5d1e8c7f2a64")]
+#[derive(Debug)]
+pub enum Module<R>
+where
+    R: Debug + Record,
+{
+    Pulse,
+    Recorder,
+    Saw,
+    Triangle,
+}
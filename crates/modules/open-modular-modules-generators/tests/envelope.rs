@@ -0,0 +1,191 @@
+use std::fmt::Debug;
+
+use open_modular_core::{
+    BUFFER_FRAMES,
+    Sample,
+};
+use open_modular_engine::module::{
+    ModuleIdentify as _,
+    module_enum,
+};
+use open_modular_modules_generators::{
+    Adsr,
+    Dahdsr,
+};
+use open_modular_testing::{
+    Harness,
+    Playback,
+    PlaybackConfig,
+    Record,
+    Recorder,
+    Recording,
+};
+use serde_json::{
+    Value,
+    json,
+};
+use uuid::Uuid;
+
+// =================================================================================================
+// Envelope
+// =================================================================================================
+
+/// The number of frames rendered.
+static FRAMES: usize = 2048;
+
+/// The number of frames of a stage of 0.01 seconds.
+static STAGE: usize = 480;
+
+/// Gets a gate which is high from the first frame given to (but not including)
+/// the second.
+fn gate(rise: usize, fall: usize) -> PlaybackConfig {
+    PlaybackConfig::new(
+        (0..fall).map(|i| if i < rise { 0. } else { 1. }).collect(),
+        false,
+    )
+}
+
+/// Gets the state of an ADSR envelope with linear stages.
+fn adsr(attack: Sample, decay: Sample, sustain: Sample, release: Sample) -> Value {
+    json!({
+        "attack": attack,
+        "decay": decay,
+        "sustain": sustain,
+        "release": release,
+        "curve": "linear",
+    })
+}
+
+/// Renders the given envelope with the given state and gate, returning the
+/// frames aligned with the gate (the gate reaches the envelope one iteration
+/// after it is output, and the output reaches the recorder one iteration
+/// later).
+fn render(id: &Uuid, state: &Value, gate: &PlaybackConfig) -> Vec<Sample> {
+    let mut harness = Harness::<Module<Recording>>::default();
+
+    let envelope = harness.add(id);
+    let gate = harness.add_with_config(&Playback::<Recording>::id(), gate);
+    let recorder = harness.add(&Recorder::<Recording>::id());
+
+    harness.restore(&envelope, state.clone());
+    harness.connect(envelope, 0, gate, 0);
+    harness.connect(recorder, 0, envelope, 0);
+    harness.process(FRAMES.div_ceil(BUFFER_FRAMES) + 2);
+
+    harness.samples()[2 * BUFFER_FRAMES..2 * BUFFER_FRAMES + FRAMES].to_vec()
+}
+
+/// Asserts that a value is within a small tolerance of the expected value.
+fn assert_value(name: &str, value: Sample, expected: Sample) {
+    assert!(
+        (value - expected).abs() < 1e-9,
+        "{name}: {value} (expected {expected})"
+    );
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Gate
+
+#[test]
+#[allow(clippy::cast_precision_loss)]
+fn gate_starts_attack_at_frame() {
+    // The gate rises part way through a block, and the attack starts at that
+    // frame (rising by one step of the attack at the frame of the edge, and
+    // reaching the peak at the last frame of the attack).
+
+    for rise in [0, 1, 100, 191] {
+        let output = render(
+            &Adsr::<Recording>::id(),
+            &adsr(0.01, 0.01, 0.5, 0.01),
+            &gate(rise, FRAMES),
+        );
+
+        assert!(
+            output[..rise].iter().all(|value| *value == 0.),
+            "before {rise}"
+        );
+        assert_value("first frame", output[rise], 1. / STAGE as Sample);
+        assert_value("peak", output[rise + STAGE - 1], 1.);
+        assert!(output[rise + STAGE] < 1., "decay after {rise}");
+    }
+}
+
+#[test]
+#[allow(clippy::cast_precision_loss)]
+fn release_during_attack_is_continuous() {
+    // The gate falls part way through the attack, and the release starts from
+    // the level reached, without a jump.
+
+    let output = render(
+        &Adsr::<Recording>::id(),
+        &adsr(0.01, 0.01, 0.5, 0.01),
+        &gate(0, 200),
+    );
+
+    let level = 200. / STAGE as Sample;
+
+    assert_value("level at release", output[199], level);
+    assert_value(
+        "first release frame",
+        output[200],
+        level * (1. - 1. / STAGE as Sample),
+    );
+    assert_value("end of release", output[200 + STAGE - 1], 0.);
+
+    for (i, pair) in output[199..200 + STAGE].windows(2).enumerate() {
+        assert!(pair[1] < pair[0], "release frame {}", 200 + i);
+    }
+}
+
+#[test]
+fn zero_time_stages() {
+    // Stages with a time of zero complete within the frame at which they are
+    // entered, so the gate moves straight to the sustain level, and the release
+    // straight to zero.
+
+    let output = render(
+        &Adsr::<Recording>::id(),
+        &adsr(0., 0., 0.5, 0.),
+        &gate(100, 300),
+    );
+
+    assert_value("before gate", output[99], 0.);
+    assert_value("gate", output[100], 0.5);
+    assert_value("before release", output[299], 0.5);
+    assert_value("release", output[300], 0.);
+
+    let output = render(
+        &Dahdsr::<Recording>::id(),
+        &json!({
+            "delay": 0.,
+            "attack": 0.,
+            "hold": 0.,
+            "decay": 0.,
+            "sustain": 0.5,
+            "release": 0.,
+            "curve": "linear",
+        }),
+        &gate(100, 300),
+    );
+
+    assert_value("before gate (delay and hold)", output[99], 0.);
+    assert_value("gate (delay and hold)", output[100], 0.5);
+    assert_value("release (delay and hold)", output[300], 0.);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Module
+
+#[module_enum(id = "c8e1f5a2-7b94-4d3c-9e60-1a4f8d2b6c37")]
+#[derive(Debug)]
+pub enum Module<R>
+where
+    R: Debug + Record,
+{
+    Adsr,
+    Dahdsr,
+    Playback,
+    Recorder,
+}
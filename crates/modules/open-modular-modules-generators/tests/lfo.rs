@@ -0,0 +1,145 @@
+use std::fmt::Debug;
+
+use open_modular_core::{
+    BUFFER_FRAMES,
+    Sample,
+};
+use open_modular_engine::module::{
+    ModuleIdentify as _,
+    module_enum,
+};
+use open_modular_modules_generators::Lfo;
+use open_modular_testing::{
+    Harness,
+    Playback,
+    PlaybackConfig,
+    Record,
+    Recorder,
+    Recording,
+};
+use serde_json::json;
+
+// =================================================================================================
+// LFO
+// =================================================================================================
+
+/// The number of frames rendered.
+static FRAMES: usize = 4800;
+
+/// The ports of the Reset and Clock inputs.
+static PORT_RESET: usize = 1;
+static PORT_CLOCK: usize = 2;
+
+/// The ports of the Random (bipolar) and Saw+ (unipolar, equal to the phase)
+/// outputs.
+static PORT_RANDOM: usize = 4;
+static PORT_SAW: usize = 7;
+
+/// Renders the given output of an LFO at 1Hz (from a phase part way through a
+/// cycle), with the given playback at the given input, returning the frames
+/// aligned with the playback (the input reaches the LFO one iteration after it
+/// is output, and the output reaches the recorder one iteration later).
+fn render(output: usize, input: usize, playback: &PlaybackConfig) -> Vec<Sample> {
+    let mut harness = Harness::<Module<Recording>>::default();
+
+    let lfo = harness.add(&Lfo::<Recording>::id());
+    let playback = harness.add_with_config(&Playback::<Recording>::id(), playback);
+    let recorder = harness.add(&Recorder::<Recording>::id());
+
+    harness.restore(&lfo, json!({ "frequency": 1., "phase": 0.3, "seed": 1 }));
+    harness.connect(lfo, input, playback, 0);
+    harness.connect(recorder, 0, lfo, output);
+    harness.process(FRAMES.div_ceil(BUFFER_FRAMES) + 2);
+
+    harness.samples()[2 * BUFFER_FRAMES..2 * BUFFER_FRAMES + FRAMES].to_vec()
+}
+
+/// Asserts that the phase (the Saw+ output) is zero at each of the given edges,
+/// and rises by the given increment for every frame until the next edge.
+#[allow(clippy::cast_precision_loss)]
+fn assert_phase(phase: &[Sample], edges: &[usize], increment: Sample) {
+    for edge in edges.windows(2) {
+        for (i, value) in phase[edge[0]..edge[1]].iter().enumerate() {
+            let expected = i as Sample * increment;
+
+            assert!(
+                (value - expected).abs() < 1e-9,
+                "frame {}: {value} (expected {expected})",
+                edge[0] + i
+            );
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Reset
+
+#[test]
+fn reset_resets_phase() {
+    // A rising edge every 1000 frames resets the phase of the free-running
+    // LFO (at 1Hz, so rising by 1 / 48000 per frame).
+
+    let phase = render(PORT_SAW, PORT_RESET, &PlaybackConfig::clock(1000));
+    let edges = (0..FRAMES).step_by(1000).collect::<Vec<_>>();
+
+    assert_phase(&phase, &edges, 1. / 48000.);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Clock
+
+#[test]
+fn clock_sets_period_and_resets_phase() {
+    // A clock with a period of 480 frames (100Hz) replaces the base frequency
+    // once the period has been measured (at the second edge), after which one
+    // cycle spans each clock period, reset at every edge.
+
+    let phase = render(PORT_SAW, PORT_CLOCK, &PlaybackConfig::clock(480));
+    let edges = (480..FRAMES).step_by(480).collect::<Vec<_>>();
+
+    assert_phase(&phase, &edges, 1. / 480.);
+
+    // Before the period is measured, the first edge resets the phase, which
+    // then rises at the base frequency.
+
+    assert_phase(&phase, &[0, 480], 1. / 48000.);
+}
+
+#[allow(clippy::float_cmp)]
+#[test]
+fn clock_holds_random_per_cycle() {
+    // The random shape holds a single value for each clock period, and takes a
+    // new value at each edge.
+
+    let random = render(PORT_RANDOM, PORT_CLOCK, &PlaybackConfig::clock(480));
+    let cycles = random.as_chunks::<480>().0;
+
+    for (i, cycle) in cycles.iter().enumerate() {
+        assert!(
+            cycle.iter().all(|value| value == &cycle[0]),
+            "cycle {i} is not held"
+        );
+        assert!((-1. ..=1.).contains(&cycle[0]), "cycle {i}: {}", cycle[0]);
+    }
+
+    for (i, pair) in cycles.windows(2).enumerate() {
+        assert_ne!(pair[0][0], pair[1][0], "cycles {i} and {}", i + 1);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Module
+
+#[module_enum(id = "6f1d9b3a-4e82-4c57-a0d6-8b2e5c7f1a49")]
+#[derive(Debug)]
+pub enum Module<R>
+where
+    R: Debug + Record,
+{
+    Lfo,
+    Playback,
+    Recorder,
+}
@@ -0,0 +1,159 @@
+use std::fmt::Debug;
+
+use open_modular_core::{
+    BUFFER_FRAMES,
+    Sample,
+};
+use open_modular_engine::module::{
+    ModuleIdentify as _,
+    module_enum,
+};
+use open_modular_modules_generators::Noise;
+use open_modular_testing::{
+    Harness,
+    Record,
+    Recorder,
+    Recording,
+    magnitudes,
+};
+use serde_json::{
+    Value,
+    json,
+};
+
+// =================================================================================================
+// Noise
+// =================================================================================================
+
+/// The outputs of the noise generator (white, pink, brown, and velvet).
+static OUTPUTS: [usize; 4] = [0, 1, 2, 3];
+
+/// The number of frames analysed for the slopes (four seconds, so that each
+/// octave band holds enough bins for the average level to be stable).
+static FRAMES: usize = 4 * 48000;
+
+/// Gets the state of a noise generator with the given seed.
+fn state(seed: u64) -> Value {
+    json!({ "amplitude": 1., "density": 2000., "seed": seed })
+}
+
+/// Renders the given output of a noise generator the given number of times
+/// within a single harness, restoring the given state before each render, and
+/// processing the given number of iterations. Each render is returned without
+/// the frames of the first iteration (the output reaches the recorder one
+/// iteration after it is generated, so the last block generated by a render is
+/// never recorded as part of it).
+fn render(output: usize, state: &Value, iterations: usize, renders: usize) -> Vec<Vec<Sample>> {
+    let mut harness = Harness::<Module<Recording>>::default();
+
+    let noise = harness.add(&Noise::<Recording>::id());
+    let recorder = harness.add(&Recorder::<Recording>::id());
+
+    harness.connect(recorder, 0, noise, output);
+
+    for _ in 0..renders {
+        harness.restore(&noise, state.clone());
+        harness.process(iterations);
+    }
+
+    let samples = harness.samples();
+
+    (0..renders)
+        .map(|render| {
+            let start = (render * iterations + 1) * BUFFER_FRAMES;
+            let end = (render + 1) * iterations * BUFFER_FRAMES;
+
+            samples[start..end].to_vec()
+        })
+        .collect()
+}
+
+/// Gets the average level (in dB) of the given spectrum over the octave band
+/// starting at the given frequency (in Hz).
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+#[allow(clippy::cast_sign_loss)]
+fn band_level(spectrum: &[Sample], frequency: Sample) -> Sample {
+    let bin = |frequency: Sample| (frequency * FRAMES as Sample / 48000.) as usize;
+    let band = &spectrum[bin(frequency)..bin(2. * frequency)];
+    let power = band
+        .iter()
+        .map(|magnitude| magnitude * magnitude)
+        .sum::<Sample>();
+
+    10. * (power / band.len() as Sample).log10()
+}
+
+/// Gets the slope (in dB per octave) of the given output, between octave bands
+/// two octaves apart.
+fn slope(output: usize) -> Sample {
+    let iterations = FRAMES / BUFFER_FRAMES + 1;
+    let spectrum = magnitudes(&render(output, &state(1), iterations, 1)[0]);
+
+    (band_level(&spectrum, 800.) - band_level(&spectrum, 200.)) / 2.
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Seed
+
+#[test]
+fn restore_reproduces_sequence() {
+    for output in OUTPUTS {
+        let renders = render(output, &state(1), 64, 2);
+
+        assert_eq!(renders[0], renders[1], "output {output}");
+        assert!(
+            renders[0].iter().any(|sample| *sample != 0.),
+            "output {output}"
+        );
+    }
+}
+
+#[test]
+fn seeds_differ() {
+    for output in OUTPUTS {
+        let a = &render(output, &state(1), 64, 1)[0];
+        let b = &render(output, &state(2), 64, 1)[0];
+
+        assert_ne!(a, b, "output {output}");
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Slopes
+
+#[test]
+fn white_slope() {
+    let slope = slope(0);
+
+    assert!(slope.abs() < 0.25, "white: {slope:.2}dB/oct");
+}
+
+#[test]
+fn pink_slope() {
+    let slope = slope(1);
+
+    assert!((slope + 3.).abs() < 0.5, "pink: {slope:.2}dB/oct");
+}
+
+#[test]
+fn brown_slope() {
+    let slope = slope(2);
+
+    assert!((slope + 6.).abs() < 0.5, "brown: {slope:.2}dB/oct");
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Module
+
+#[module_enum(id = "b2c6e8f4-1a3d-4e97-8b50-6d9f2c4a7e13")]
+#[derive(Debug)]
+pub enum Module<R>
+where
+    R: Debug + Record,
+{
+    Noise,
+    Recorder,
+}
@@ -0,0 +1,116 @@
+use std::fmt::Debug;
+
+use open_modular_core::{
+    BUFFER_FRAMES,
+    Sample,
+};
+use open_modular_engine::module::{
+    ModuleIdentify as _,
+    module_enum,
+};
+use open_modular_modules_generators::Sine;
+use open_modular_testing::{
+    Harness,
+    Playback,
+    PlaybackConfig,
+    Record,
+    Recorder,
+    Recording,
+    magnitudes,
+};
+use serde_json::json;
+
+// =================================================================================================
+// Oscillator
+// =================================================================================================
+
+/// The number of frames analysed (one second, so that each bin of the spectrum
+/// is exactly 1Hz).
+static FRAMES: usize = 48000;
+
+/// The ports of the control inputs of the sine oscillator (following the
+/// common inputs).
+static PORT_FINE: usize = 4;
+static PORT_AMPLITUDE: usize = 5;
+
+/// Renders a sine oscillator with the given controls (restored once, before
+/// processing), and the given constant value at the given input (where given),
+/// returning the frames after the first two iterations (the input reaches the
+/// oscillator one iteration after it is output, and the output reaches the
+/// recorder one iteration later).
+fn render(amplitude: Sample, fine: Sample, input: Option<(usize, Sample)>) -> Vec<Sample> {
+    let mut harness = Harness::<Module<Recording>>::default();
+
+    let sine = harness.add(&Sine::<Recording>::id());
+    let recorder = harness.add(&Recorder::<Recording>::id());
+
+    harness.restore(
+        &sine,
+        json!({ "amplitude": amplitude, "fine": fine, "frequency": 440., "phase": 0. }),
+    );
+
+    if let Some((port, value)) = input {
+        let playback = harness.add_with_config(
+            &Playback::<Recording>::id(),
+            &PlaybackConfig::new(vec![value], true),
+        );
+
+        harness.connect(sine, port, playback, 0);
+    }
+
+    harness.connect(recorder, 0, sine, 0);
+    harness.process(FRAMES.div_ceil(BUFFER_FRAMES) + 2);
+
+    harness.samples()[2 * BUFFER_FRAMES..2 * BUFFER_FRAMES + FRAMES].to_vec()
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Controls
+
+#[test]
+fn amplitude_input_adds_to_amplitude() {
+    // The amplitude does not affect the phase, so an oscillator with the
+    // amplitude set by the input matches one with the amplitude set by the
+    // state, frame for frame.
+
+    let input = render(0.25, 0., Some((PORT_AMPLITUDE, 0.5)));
+    let state = render(0.75, 0., None);
+
+    for (i, (input, state)) in input.iter().zip(&state).enumerate() {
+        assert!(
+            (input - state).abs() < 1e-12,
+            "frame {i}: {input} != {state}"
+        );
+    }
+}
+
+#[test]
+fn fine_input_adds_to_fine_tuning() {
+    // At 100 cents per volt, 6V added to a fine tuning of 600 cents is an
+    // octave above the base frequency.
+
+    let spectrum = magnitudes(&render(1., 600., Some((PORT_FINE, 6.))));
+    let peak = spectrum
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(bin, _)| bin);
+
+    assert_eq!(peak, Some(880));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Module
+
+#[module_enum(id = "e4a7c2d9-8b31-4f5e-9d06-2c8f1b7a3e54")]
+#[derive(Debug)]
+pub enum Module<R>
+where
+    R: Debug + Record,
+{
+    Playback,
+    Recorder,
+    Sine,
+}
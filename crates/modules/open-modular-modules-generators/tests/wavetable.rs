@@ -0,0 +1,280 @@
+#![feature(portable_simd)]
+
+use std::{
+    env,
+    f64::consts::TAU,
+    fmt::Debug,
+    fs,
+    path::PathBuf,
+    process,
+};
+
+use hound::{
+    SampleFormat,
+    WavSpec,
+    WavWriter,
+};
+use open_modular_core::{
+    BUFFER_FRAMES,
+    Sample,
+    Vector,
+};
+use open_modular_engine::{
+    catalog::ModuleCatalog as _,
+    module::{
+        ModuleIdentify as _,
+        Version,
+        module_enum,
+    },
+    patch::{
+        Patch,
+        PatchError,
+        PatchInstance,
+    },
+    protocol::Protocol,
+};
+use open_modular_modules_generators::{
+    FRAME_SAMPLES,
+    Table,
+    Wavetable,
+    WavetableConfig,
+};
+use open_modular_testing::{
+    Harness,
+    Playback,
+    PlaybackConfig,
+    Record,
+    Recorder,
+    Recording,
+};
+use serde_json::{
+    Value,
+    json,
+};
+use uuid::Uuid;
+
+// =================================================================================================
+// Wavetable
+// =================================================================================================
+
+/// Gets a patch of a single wavetable oscillator with the given configuration.
+fn patch(config: Value) -> Patch {
+    let mut instance =
+        PatchInstance::new(Uuid::new_v4(), Wavetable::<()>::id(), Version::new(1, 0, 0));
+
+    instance.config = Some(config);
+
+    Patch::new(vec![instance], Vec::new())
+}
+
+/// Gets the path of a temporary wavetable file with the given name.
+fn path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("wavetable-{name}-{}.wav", process::id()))
+}
+
+/// Gets a single frame of a sine with the given amplitude.
+#[allow(clippy::cast_precision_loss)]
+fn sine(amplitude: Sample) -> Vec<Sample> {
+    (0..FRAME_SAMPLES)
+        .map(|i| (i as Sample / FRAME_SAMPLES as Sample * TAU).sin() * amplitude)
+        .collect()
+}
+
+/// Writes the given samples (in [-1, 1]) to a wavetable file with the given
+/// sample format and bits per sample.
+#[allow(clippy::cast_possible_truncation)]
+fn write(path: &PathBuf, samples: &[Sample], sample_format: SampleFormat, bits_per_sample: u16) {
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate: 48000,
+        bits_per_sample,
+        sample_format,
+    };
+
+    let mut writer = WavWriter::create(path, spec).expect("writer to be created");
+    let scale = Sample::from(2_u32).powi(i32::from(bits_per_sample) - 1);
+
+    for sample in samples {
+        match sample_format {
+            SampleFormat::Float => writer.write_sample(*sample as f32),
+            SampleFormat::Int => writer.write_sample((sample * scale) as i32),
+        }
+        .expect("sample to be written");
+    }
+
+    writer.finalize().expect("writer to be finalized");
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Config
+
+#[test]
+fn config_round_trip() {
+    let path = path("round-trip");
+    let samples = (0..256_u16)
+        .map(|i| Sample::from(i) / 128. - 1.)
+        .collect::<Vec<_>>();
+
+    write(&path, &samples, SampleFormat::Float, 32);
+
+    let value = json!({ "path": path });
+    let config = serde_json::from_value::<WavetableConfig>(value.clone());
+
+    assert!(matches!(
+        config.map(|config| serde_json::to_value(config).expect("config to serialize")),
+        Ok(config) if config == value
+    ));
+
+    drop(fs::remove_file(path));
+}
+
+#[test]
+fn config_default() {
+    let config = serde_json::from_value::<WavetableConfig>(json!({}));
+
+    assert!(matches!(
+        config.map(|config| serde_json::to_value(config).expect("config to serialize")),
+        Ok(config) if config == json!({})
+    ));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Load
+
+#[test]
+fn load_wavetable_missing() {
+    let protocols = patch(json!({ "path": "missing.wav" }))
+        .load::<Module<Recording>>(&Module::<Recording>::catalog());
+
+    assert!(matches!(protocols, Err(PatchError::Config { .. })));
+}
+
+#[test]
+fn load_wavetable_default() {
+    let protocols = patch(json!({})).load::<Module<Recording>>(&Module::<Recording>::catalog());
+
+    assert!(matches!(protocols.as_deref(), Ok([Protocol::Add(_)])));
+}
+
+#[test]
+fn load_table_int() {
+    // Integer samples are scaled to [-1, 1] at every bit depth (including 32
+    // bits, where the scale exceeds the range of an i32).
+
+    for bits_per_sample in [16, 24, 32] {
+        let path = path(&format!("int-{bits_per_sample}"));
+
+        write(&path, &sine(0.5), SampleFormat::Int, bits_per_sample);
+
+        let table = Table::load(&path).expect("table to load");
+        let peak = table.read(0, &Vector::splat(0.25), &Vector::splat(0.));
+
+        assert!(
+            (peak.as_array()[0] - 0.5).abs() < 1e-4,
+            "{bits_per_sample} bits: {}",
+            peak.as_array()[0]
+        );
+
+        drop(fs::remove_file(path));
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Controls
+
+/// The number of frames compared.
+static FRAMES: usize = 4800;
+
+/// The ports of the Position and Amplitude inputs of the wavetable oscillator.
+static PORT_POSITION: usize = 4;
+static PORT_AMPLITUDE: usize = 6;
+
+/// Renders a wavetable oscillator using the wavetable file at the given path,
+/// with the given controls (restored once, before processing), and the given
+/// constant value at the given input (where given), returning the frames after
+/// the first two iterations (the input reaches the oscillator one iteration
+/// after it is output, and the output reaches the recorder one iteration
+/// later).
+fn render(path: &PathBuf, state: &Value, input: Option<(usize, Sample)>) -> Vec<Sample> {
+    let mut harness = Harness::<Module<Recording>>::default();
+
+    let wavetable = harness.add_with_config(
+        &Wavetable::<Recording>::id(),
+        &WavetableConfig::load(path).expect("config to load"),
+    );
+    let recorder = harness.add(&Recorder::<Recording>::id());
+
+    harness.restore(&wavetable, state.clone());
+
+    if let Some((port, value)) = input {
+        let playback = harness.add_with_config(
+            &Playback::<Recording>::id(),
+            &PlaybackConfig::new(vec![value], true),
+        );
+
+        harness.connect(wavetable, port, playback, 0);
+    }
+
+    harness.connect(recorder, 0, wavetable, 0);
+    harness.process(FRAMES.div_ceil(BUFFER_FRAMES) + 2);
+
+    harness.samples()[2 * BUFFER_FRAMES..2 * BUFFER_FRAMES + FRAMES].to_vec()
+}
+
+/// Gets the state of a wavetable oscillator with the given amplitude and
+/// position.
+fn state(amplitude: Sample, position: Sample) -> Value {
+    json!({
+        "amplitude": amplitude,
+        "fine": 0.,
+        "frequency": 440.,
+        "phase": 0.,
+        "position": position,
+    })
+}
+
+/// Asserts that two renders are identical, frame for frame.
+fn assert_identical(a: &[Sample], b: &[Sample]) {
+    for (i, (a, b)) in a.iter().zip(b).enumerate() {
+        assert!((a - b).abs() < 1e-12, "frame {i}: {a} != {b}");
+    }
+}
+
+#[test]
+fn inputs_add_to_controls() {
+    // A table of two frames, a sine and a sine at half amplitude.
+
+    let path = path("controls");
+    let samples = [sine(1.), sine(0.5)].concat();
+
+    write(&path, &samples, SampleFormat::Float, 32);
+
+    assert_identical(
+        &render(&path, &state(1., 0.25), Some((PORT_POSITION, 0.5))),
+        &render(&path, &state(1., 0.75), None),
+    );
+    assert_identical(
+        &render(&path, &state(0.25, 0.), Some((PORT_AMPLITUDE, 0.5))),
+        &render(&path, &state(0.75, 0.), None),
+    );
+
+    drop(fs::remove_file(path));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Module
+
+#[module_enum(id = "5a0c8e27-3d91-4b6f-8e42-c7d1f9a3b058")]
+#[derive(Debug)]
+pub enum Module<R>
+where
+    R: Debug + Record,
+{
+    Playback,
+    Recorder,
+    Wavetable,
+}
@@ -2,7 +2,10 @@
 
 use derive_more::with_trait::Debug;
 use fancy_constructor::new;
-use open_modular_core::MAX_CHANNELS;
+use open_modular_core::{
+    MAX_CHANNELS,
+    Vector,
+};
 use open_modular_engine::{
     self,
     module::{
@@ -10,10 +13,12 @@ use open_modular_engine::{
         ModuleDefinition,
         ModuleDefinitionBuilder,
         ModuleInstantiate,
+        Version,
         module,
     },
     port::{
         Port,
+        PortIndex,
         PortInputVectorGet,
         PortInputs,
         PortOutputs,
@@ -57,7 +62,14 @@ where
         (0..u32::try_from(MAX_CHANNELS).expect("invalid channel maximum")).fold(
             module
                 .name("audio/out")
-                .description("Multi-Channel Audio Output"),
+                .version(Version::new(1, 0, 0))
+                .category("IO")
+                .description("Multi-Channel Audio Output")
+                .author("Andrew Cherry (@kolektiv)")
+                .license("AGPL-3.0-only")
+                .with_tag("audio")
+                .with_tag("io")
+                .sink(true),
             |module, i| module.with_input(|input| input.name(format!("Channel {i}"))),
         )
     }
@@ -71,6 +83,7 @@ where
 
     fn instantiate(
         context: Self::Context,
+        _config: Self::Config,
         port_inputs: PortInputs,
         port_outputs: PortOutputs,
     ) -> Self {
@@ -96,7 +109,7 @@ where
                         if let Some(Port::Connected(input)) =
                             self.port_inputs.vector(i, &args.token)
                         {
-                            output_vector.clone_from(input);
+                            *output_vector = input * args.gain;
                         }
                     });
             }
@@ -116,6 +129,19 @@ where
             }
         }
     }
+
+    fn on_disconnect(&mut self, port: PortIndex) {
+        // Clear the output channel of a disconnected input, which would
+        // otherwise continue to output the last vector written to it.
+
+        if let (OutputState::Active(output_buffer), PortIndex::Input(i)) = (&self.state, port) {
+            let output_buffer = unsafe { &mut *output_buffer.0.get() };
+
+            if let Some(output_vector) = output_buffer.get_mut(i) {
+                *output_vector = Vector::default();
+            }
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
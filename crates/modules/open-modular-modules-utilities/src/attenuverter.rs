@@ -0,0 +1,142 @@
+use std::marker::PhantomData;
+
+use fancy_constructor::new;
+use open_modular_core::{
+    Sample,
+    Vector,
+};
+use open_modular_engine::{
+    module::{
+        ModuleDefine,
+        ModuleDefinition,
+        ModuleDefinitionBuilder,
+        ModuleInstantiate,
+        ModuleState,
+        Version,
+        module,
+    },
+    port::{
+        Port,
+        PortInputVectorGet as _,
+        PortInputs,
+        PortOutputVectorGet as _,
+        PortOutputs,
+    },
+    processor::{
+        Process,
+        ProcessArgs,
+    },
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+// =================================================================================================
+// Attenuverter
+// =================================================================================================
+
+#[module(id = "b7d3a9e4-1f62-4c8b-a5d0-94e6f2c1b378", state)]
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct Attenuverter<R> {
+    #[new(default)]
+    state: AttenuverterState,
+
+    port_inputs: PortInputs,
+    port_outputs: PortOutputs,
+    #[new(default)]
+    _r: PhantomData<R>,
+}
+
+impl<R> ModuleDefine for Attenuverter<R> {
+    fn define(module: ModuleDefinitionBuilder) -> impl Into<ModuleDefinition> {
+        module
+            .name("util/attenuverter")
+            .version(Version::new(1, 0, 0))
+            .category("Utility")
+            .description("Attenuverter and Offset")
+            .usage(
+                "The output is the input scaled by the gain (where a negative gain inverts the \
+                 input), plus the offset. The Gain CV and Offset CV inputs are added to the gain \
+                 and offset respectively. With no input, the output is the offset alone.",
+            )
+            .author("Andrew Cherry (@kolektiv)")
+            .license("AGPL-3.0-only")
+            .with_tag("utility")
+            .with_input(|input| input.name("Input"))
+            .with_input(|input| {
+                input
+                    .name("Gain CV")
+                    .description("Gain (added to the gain)")
+            })
+            .with_input(|input| {
+                input
+                    .name("Offset CV")
+                    .description("Offset (added to the offset)")
+            })
+            .with_output(|output| output.name("Output"))
+    }
+}
+
+impl<R> ModuleInstantiate for Attenuverter<R> {
+    type Context = R;
+
+    fn instantiate(
+        _context: Self::Context,
+        _config: Self::Config,
+        port_inputs: PortInputs,
+        port_outputs: PortOutputs,
+    ) -> Self {
+        Self::new(port_inputs, port_outputs)
+    }
+}
+
+impl<R> ModuleState for Attenuverter<R> {
+    type State = AttenuverterState;
+
+    fn state(&self) -> Self::State {
+        self.state
+    }
+
+    fn restore(&mut self, state: Self::State) {
+        self.state = state;
+    }
+}
+
+impl<R> Process for Attenuverter<R> {
+    fn process(&mut self, args: &ProcessArgs) {
+        if let Some(Port::Connected(output)) = self.port_outputs.vector(0, &args.token) {
+            let input = |port, value| match self.port_inputs.vector(port, &args.token) {
+                Some(Port::Connected(input)) => input + Vector::splat(value),
+                _ => Vector::splat(value),
+            };
+
+            let gain = input(1, self.state.gain);
+            let offset = input(2, self.state.offset);
+
+            *output = match self.port_inputs.vector(0, &args.token) {
+                Some(Port::Connected(input)) => input * gain + offset,
+                _ => offset,
+            };
+        }
+    }
+}
+
+// State
+
+/// The state of an attenuverter (the controls, which can be set by restoring a
+/// state).
+#[derive(new, Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct AttenuverterState {
+    /// The gain (negative values invert the input)
+    gain: Sample,
+    /// The offset, added after the gain
+    offset: Sample,
+}
+
+impl Default for AttenuverterState {
+    fn default() -> Self {
+        Self::new(1., 0.)
+    }
+}
@@ -0,0 +1,88 @@
+use std::marker::PhantomData;
+
+use fancy_constructor::new;
+use open_modular_engine::{
+    module::{
+        ModuleDefine,
+        ModuleDefinition,
+        ModuleDefinitionBuilder,
+        ModuleInstantiate,
+        Version,
+        module,
+    },
+    port::{
+        Port,
+        PortInputVectorGet as _,
+        PortInputs,
+        PortOutputs,
+    },
+    processor::{
+        Process,
+        ProcessArgs,
+    },
+};
+
+use crate::multiple::{
+    self,
+    MultipleConfig,
+};
+
+// =================================================================================================
+// Inverting Multiple
+// =================================================================================================
+
+#[module(id = "3802c9f7-dc61-49c9-bb51-b253f784dfc9", config)]
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct InvertingMultiple<R> {
+    config: MultipleConfig,
+    port_inputs: PortInputs,
+    port_outputs: PortOutputs,
+    #[new(default)]
+    _r: PhantomData<R>,
+}
+
+impl<R> ModuleDefine for InvertingMultiple<R> {
+    fn define(module: ModuleDefinitionBuilder) -> impl Into<ModuleDefinition> {
+        module
+            .name("util/mult-inverting")
+            .version(Version::new(1, 0, 0))
+            .category("Utility")
+            .description("Inverting Multiple")
+            .usage(
+                "Copies the inverted input to every output. The number of outputs (4 by default) \
+                 is set by the configuration of the instance.",
+            )
+            .author("Andrew Cherry (@kolektiv)")
+            .license("AGPL-3.0-only")
+            .with_tag("utility")
+            .sleep(true)
+            .with_input(|input| input.name("Input"))
+    }
+}
+
+impl<R> ModuleInstantiate for InvertingMultiple<R> {
+    type Config = MultipleConfig;
+    type Context = R;
+
+    fn configure(definition: ModuleDefinition, config: &Self::Config) -> ModuleDefinition {
+        multiple::configure(definition, "Inverting Multiple", config.outputs)
+    }
+
+    fn instantiate(
+        _context: Self::Context,
+        config: Self::Config,
+        port_inputs: PortInputs,
+        port_outputs: PortOutputs,
+    ) -> Self {
+        Self::new(config, port_inputs, port_outputs)
+    }
+}
+
+impl<R> Process for InvertingMultiple<R> {
+    fn process(&mut self, args: &ProcessArgs) {
+        if let Some(Port::Connected(input)) = self.port_inputs.vector(0, &args.token) {
+            multiple::distribute(&mut self.port_outputs, args, &-*input);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use std::{
+    marker::PhantomData,
+    simd::num::SimdFloat as _,
+};
+
+use fancy_constructor::new;
+use open_modular_core::Vector;
+use open_modular_engine::{
+    module::{
+        ModuleDefine,
+        ModuleDefinition,
+        ModuleDefinitionBuilder,
+        ModuleInstantiate,
+        Version,
+        module,
+    },
+    port::{
+        Port,
+        PortInputVectorGet as _,
+        PortInputs,
+        PortOutputVectorGet as _,
+        PortOutputs,
+    },
+    processor::{
+        Process,
+        ProcessArgs,
+    },
+};
+
+// =================================================================================================
+// Math
+// =================================================================================================
+
+#[module(id = "e5c1d7b3-8a24-4f6e-9b07-3c8e2a5d4f19")]
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct Math<R> {
+    port_inputs: PortInputs,
+    port_outputs: PortOutputs,
+    #[new(default)]
+    _r: PhantomData<R>,
+}
+
+impl<R> ModuleDefine for Math<R> {
+    fn define(module: ModuleDefinitionBuilder) -> impl Into<ModuleDefinition> {
+        module
+            .name("util/math")
+            .version(Version::new(1, 0, 0))
+            .category("Utility")
+            .description("Math (Add, Subtract, Multiply, Min, Max, Abs)")
+            .usage(
+                "Each output is the result of an operation on the A and B inputs (where an \
+                 unconnected input is treated as 0V). Only connected outputs are computed.",
+            )
+            .author("Andrew Cherry (@kolektiv)")
+            .license("AGPL-3.0-only")
+            .with_tag("math")
+            .with_tag("utility")
+            .sleep(true)
+            .with_input(|input| input.name("A"))
+            .with_input(|input| input.name("B"))
+            .with_output(|output| output.name("Add").description("A + B"))
+            .with_output(|output| output.name("Subtract").description("A - B"))
+            .with_output(|output| output.name("Multiply").description("A * B"))
+            .with_output(|output| output.name("Min").description("Minimum of A and B"))
+            .with_output(|output| output.name("Max").description("Maximum of A and B"))
+            .with_output(|output| output.name("Abs").description("Absolute value of A"))
+    }
+}
+
+impl<R> ModuleInstantiate for Math<R> {
+    type Context = R;
+
+    fn instantiate(
+        _context: Self::Context,
+        _config: Self::Config,
+        port_inputs: PortInputs,
+        port_outputs: PortOutputs,
+    ) -> Self {
+        Self::new(port_inputs, port_outputs)
+    }
+}
+
+impl<R> Process for Math<R> {
+    fn process(&mut self, args: &ProcessArgs) {
+        let input = |port| match self.port_inputs.vector(port, &args.token) {
+            Some(Port::Connected(input)) => *input,
+            _ => Vector::splat(0.),
+        };
+
+        let a = input(0);
+        let b = input(1);
+
+        for port in 0..6 {
+            if let Some(Port::Connected(output)) = self.port_outputs.vector(port, &args.token) {
+                *output = match port {
+                    0 => a + b,
+                    1 => a - b,
+                    2 => a * b,
+                    3 => a.simd_min(b),
+                    4 => a.simd_max(b),
+                    _ => a.abs(),
+                };
+            }
+        }
+    }
+}
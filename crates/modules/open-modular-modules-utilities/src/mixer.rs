@@ -0,0 +1,189 @@
+use std::marker::PhantomData;
+
+use fancy_constructor::new;
+use open_modular_core::{
+    Sample,
+    Vector,
+};
+use open_modular_engine::{
+    module::{
+        ModuleDefine,
+        ModuleDefinition,
+        ModuleDefinitionBuilder,
+        ModuleInstantiate,
+        ModuleState,
+        Version,
+        module,
+    },
+    port::{
+        Port,
+        PortInputDefinition,
+        PortInputVectorGet as _,
+        PortInputs,
+        PortOutputVectorGet as _,
+        PortOutputs,
+    },
+    processor::{
+        Process,
+        ProcessArgs,
+    },
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+// =================================================================================================
+// Mixer
+// =================================================================================================
+
+#[module(id = "2a6f8d13-c4e7-4b95-8e0a-5d1b3c9f7e42", config, state)]
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct Mixer<R> {
+    config: MixerConfig,
+    state: MixerState,
+
+    port_inputs: PortInputs,
+    port_outputs: PortOutputs,
+    #[new(default)]
+    _r: PhantomData<R>,
+}
+
+impl<R> ModuleDefine for Mixer<R> {
+    fn define(module: ModuleDefinitionBuilder) -> impl Into<ModuleDefinition> {
+        module
+            .name("util/mixer")
+            .version(Version::new(1, 1, 0))
+            .category("Utility")
+            .description("Mixer")
+            .usage(
+                "The output is the sum of the channel inputs, each scaled by the level of the \
+                 channel and the matching Level input (where an unconnected Level input is \
+                 treated as 1V), scaled by the master level. The number of channels (4 by \
+                 default) is set by the configuration of the instance.",
+            )
+            .author("Andrew Cherry (@kolektiv)")
+            .license("AGPL-3.0-only")
+            .with_tag("mixer")
+            .with_tag("utility")
+            .sleep(true)
+            .with_output(|output| output.name("Mix"))
+    }
+}
+
+impl<R> ModuleInstantiate for Mixer<R> {
+    type Config = MixerConfig;
+    type Context = R;
+
+    /// Configures the definition with the given number of channels (at least
+    /// one), where the channel inputs are ports zero to `channels - 1`, and the
+    /// level inputs follow in the same order.
+    fn configure(mut definition: ModuleDefinition, config: &Self::Config) -> ModuleDefinition {
+        let channels = config.channels.max(1);
+
+        let inputs = (1..=channels).map(|i| {
+            PortInputDefinition::builder()
+                .name(format!("Channel {i}"))
+                .into()
+        });
+
+        let levels = (1..=channels).map(|i| {
+            PortInputDefinition::builder()
+                .name(format!("Level {i}"))
+                .description(format!("Level of channel {i} (multiplied by the level)"))
+                .into()
+        });
+
+        definition.description = Some(format!("Mixer ({channels}-Channel)"));
+        definition.inputs = inputs.chain(levels).collect();
+        definition
+    }
+
+    fn instantiate(
+        _context: Self::Context,
+        config: Self::Config,
+        port_inputs: PortInputs,
+        port_outputs: PortOutputs,
+    ) -> Self {
+        let state = MixerState::new(vec![1.; config.channels.max(1)], 1.);
+
+        Self::new(config, state, port_inputs, port_outputs)
+    }
+}
+
+impl<R> ModuleState for Mixer<R> {
+    type State = MixerState;
+
+    fn state(&self) -> Self::State {
+        self.state.clone()
+    }
+
+    fn restore(&mut self, state: Self::State) {
+        self.state = state;
+    }
+}
+
+impl<R> Process for Mixer<R> {
+    fn process(&mut self, args: &ProcessArgs) {
+        if let Some(Port::Connected(output)) = self.port_outputs.vector(0, &args.token) {
+            let channels = self.port_inputs.len() / 2;
+            let mut mix = Vector::splat(0.);
+
+            for channel in 0..channels {
+                if let Some(Port::Connected(input)) = self.port_inputs.vector(channel, &args.token)
+                {
+                    let level = Vector::splat(self.state.level(channel));
+
+                    mix += match self.port_inputs.vector(channels + channel, &args.token) {
+                        Some(Port::Connected(cv)) => input * level * cv,
+                        _ => input * level,
+                    };
+                }
+            }
+
+            *output = mix * Vector::splat(self.state.master);
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Config
+
+/// The configuration of a mixer.
+#[derive(new, Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MixerConfig {
+    /// The number of channels (at least one)
+    channels: usize,
+}
+
+impl Default for MixerConfig {
+    /// A default configuration of four channels.
+    fn default() -> Self {
+        Self::new(4)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// State
+
+/// The state of a mixer (the controls, which can be set by restoring a state).
+#[derive(new, Clone, Debug, Deserialize, Serialize)]
+pub struct MixerState {
+    /// The level of each channel
+    levels: Vec<Sample>,
+    /// The master level, applied to the mix
+    master: Sample,
+}
+
+impl MixerState {
+    /// Gets the level of the given channel (where a restored state has fewer
+    /// levels than the mixer has channels, the remaining channels are at unity
+    /// level).
+    fn level(&self, channel: usize) -> Sample {
+        self.levels.get(channel).copied().unwrap_or(1.)
+    }
+}
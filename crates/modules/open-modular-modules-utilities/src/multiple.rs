@@ -0,0 +1,144 @@
+use std::marker::PhantomData;
+
+use fancy_constructor::new;
+use open_modular_core::Vector;
+use open_modular_engine::{
+    module::{
+        ModuleDefine,
+        ModuleDefinition,
+        ModuleDefinitionBuilder,
+        ModuleInstantiate,
+        Version,
+        module,
+    },
+    port::{
+        Port,
+        PortInputVectorGet as _,
+        PortInputs,
+        PortOutputDefinition,
+        PortOutputVectorGet as _,
+        PortOutputs,
+    },
+    processor::{
+        Process,
+        ProcessArgs,
+    },
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+// =================================================================================================
+// Multiple
+// =================================================================================================
+
+#[module(id = "54d93000-7dd2-45ce-a3f1-ad53b0a04fac", config)]
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct Multiple<R> {
+    config: MultipleConfig,
+    port_inputs: PortInputs,
+    port_outputs: PortOutputs,
+    #[new(default)]
+    _r: PhantomData<R>,
+}
+
+impl<R> ModuleDefine for Multiple<R> {
+    fn define(module: ModuleDefinitionBuilder) -> impl Into<ModuleDefinition> {
+        module
+            .name("util/mult")
+            .version(Version::new(1, 1, 0))
+            .category("Utility")
+            .description("Multiple")
+            .usage(
+                "Copies the input to every output. The number of outputs (4 by default) is set by \
+                 the configuration of the instance.",
+            )
+            .author("Andrew Cherry (@kolektiv)")
+            .license("AGPL-3.0-only")
+            .with_tag("utility")
+            .sleep(true)
+            .with_input(|input| input.name("Input"))
+    }
+}
+
+impl<R> ModuleInstantiate for Multiple<R> {
+    type Config = MultipleConfig;
+    type Context = R;
+
+    fn configure(definition: ModuleDefinition, config: &Self::Config) -> ModuleDefinition {
+        configure(definition, "Multiple", config.outputs)
+    }
+
+    fn instantiate(
+        _context: Self::Context,
+        config: Self::Config,
+        port_inputs: PortInputs,
+        port_outputs: PortOutputs,
+    ) -> Self {
+        Self::new(config, port_inputs, port_outputs)
+    }
+}
+
+impl<R> Process for Multiple<R> {
+    fn process(&mut self, args: &ProcessArgs) {
+        if let Some(Port::Connected(input)) = self.port_inputs.vector(0, &args.token) {
+            distribute(&mut self.port_outputs, args, input);
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Config
+
+/// The configuration of a multiple (or an inverting multiple).
+#[derive(new, Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MultipleConfig {
+    /// The number of outputs (at least one)
+    pub(crate) outputs: usize,
+}
+
+impl Default for MultipleConfig {
+    /// A default configuration of four outputs.
+    fn default() -> Self {
+        Self::new(4)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Functions
+
+/// Configures the definition of a multiple with the given number of outputs
+/// (at least one), describing the multiple by the number of outputs.
+pub(crate) fn configure(
+    mut definition: ModuleDefinition,
+    name: &str,
+    outputs: usize,
+) -> ModuleDefinition {
+    let outputs = outputs.max(1);
+
+    definition.description = Some(format!("{name} ({outputs}-Way)"));
+    definition.outputs = (0..outputs)
+        .map(|i| {
+            PortOutputDefinition::builder()
+                .name(format!("Output {i}"))
+                .into()
+        })
+        .collect();
+
+    definition
+}
+
+/// Copies a value to every connected output (outputs which are not connected
+/// are skipped).
+pub(crate) fn distribute(port_outputs: &mut PortOutputs, args: &ProcessArgs, value: &Vector) {
+    for port in 0..port_outputs.len() {
+        if let Some(Port::Connected(output)) = port_outputs.vector(port, &args.token) {
+            *output = *value;
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use std::marker::PhantomData;
+
+use fancy_constructor::new;
+use open_modular_core::Vector;
+use open_modular_engine::{
+    module::{
+        ModuleDefine,
+        ModuleDefinition,
+        ModuleDefinitionBuilder,
+        ModuleInstantiate,
+        Version,
+        module,
+    },
+    port::{
+        Port,
+        PortInputDefinition,
+        PortInputVectorGet as _,
+        PortInputs,
+        PortOutputs,
+    },
+    processor::{
+        Process,
+        ProcessArgs,
+    },
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::multiple;
+
+// =================================================================================================
+// Stacking Multiple
+// =================================================================================================
+
+#[module(id = "68e2875d-1a6f-4eb3-a7d0-42cafa3b8723", config)]
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct StackingMultiple<R> {
+    config: StackingMultipleConfig,
+    port_inputs: PortInputs,
+    port_outputs: PortOutputs,
+    #[new(default)]
+    _r: PhantomData<R>,
+}
+
+impl<R> ModuleDefine for StackingMultiple<R> {
+    fn define(module: ModuleDefinitionBuilder) -> impl Into<ModuleDefinition> {
+        module
+            .name("util/mult-stacking")
+            .version(Version::new(1, 0, 0))
+            .category("Utility")
+            .description("Stacking Multiple")
+            .usage(
+                "Copies the sum of the connected inputs to every output (as when stacking cables \
+                 on a single jack). The number of inputs and outputs (4 of each by default) is \
+                 set by the configuration of the instance.",
+            )
+            .author("Andrew Cherry (@kolektiv)")
+            .license("AGPL-3.0-only")
+            .with_tag("utility")
+            .sleep(true)
+    }
+}
+
+impl<R> ModuleInstantiate for StackingMultiple<R> {
+    type Config = StackingMultipleConfig;
+    type Context = R;
+
+    fn configure(mut definition: ModuleDefinition, config: &Self::Config) -> ModuleDefinition {
+        definition.inputs = (0..config.inputs.max(1))
+            .map(|i| {
+                PortInputDefinition::builder()
+                    .name(format!("Input {i}"))
+                    .into()
+            })
+            .collect();
+
+        multiple::configure(definition, "Stacking Multiple", config.outputs)
+    }
+
+    fn instantiate(
+        _context: Self::Context,
+        config: Self::Config,
+        port_inputs: PortInputs,
+        port_outputs: PortOutputs,
+    ) -> Self {
+        Self::new(config, port_inputs, port_outputs)
+    }
+}
+
+impl<R> Process for StackingMultiple<R> {
+    fn process(&mut self, args: &ProcessArgs) {
+        let mut sum = None;
+
+        for port in 0..self.port_inputs.len() {
+            if let Some(Port::Connected(input)) = self.port_inputs.vector(port, &args.token) {
+                *sum.get_or_insert(Vector::splat(0.)) += input;
+            }
+        }
+
+        if let Some(sum) = sum {
+            multiple::distribute(&mut self.port_outputs, args, &sum);
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Config
+
+/// The configuration of a stacking multiple.
+#[derive(new, Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct StackingMultipleConfig {
+    /// The number of inputs (at least one)
+    inputs: usize,
+    /// The number of outputs (at least one)
+    outputs: usize,
+}
+
+impl Default for StackingMultipleConfig {
+    /// A default configuration of four inputs and four outputs.
+    fn default() -> Self {
+        Self::new(4, 4)
+    }
+}
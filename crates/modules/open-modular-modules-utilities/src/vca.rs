@@ -0,0 +1,172 @@
+use std::{
+    marker::PhantomData,
+    simd::{
+        Select as _,
+        StdFloat as _,
+        cmp::SimdPartialOrd as _,
+        num::SimdFloat as _,
+    },
+};
+
+use fancy_constructor::new;
+use open_modular_core::{
+    Sample,
+    Vector,
+};
+use open_modular_engine::{
+    module::{
+        ModuleDefine,
+        ModuleDefinition,
+        ModuleDefinitionBuilder,
+        ModuleInstantiate,
+        ModuleState,
+        Version,
+        module,
+    },
+    port::{
+        Port,
+        PortInputVectorGet as _,
+        PortInputs,
+        PortOutputVectorGet as _,
+        PortOutputs,
+    },
+    processor::{
+        Process,
+        ProcessArgs,
+    },
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+// =================================================================================================
+// VCA
+// =================================================================================================
+
+/// The range of the exponential response, in decibels (the gain at a CV of
+/// zero, above which the response rises to unity gain at a CV of one).
+static EXPONENTIAL_RANGE: Sample = 60.;
+
+#[module(id = "8e1b5f2a-6c39-4d07-9f4e-b3a2c7d81e56", state)]
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct Vca<R> {
+    #[new(default)]
+    state: VcaState,
+
+    port_inputs: PortInputs,
+    port_outputs: PortOutputs,
+    #[new(default)]
+    _r: PhantomData<R>,
+}
+
+impl<R> ModuleDefine for Vca<R> {
+    fn define(module: ModuleDefinitionBuilder) -> impl Into<ModuleDefinition> {
+        module
+            .name("util/vca")
+            .version(Version::new(1, 0, 0))
+            .category("Utility")
+            .description("Voltage Controlled Amplifier")
+            .usage(
+                "The input is scaled by the level and the response to the CV input (where an \
+                 unconnected CV input is treated as 1V). The linear response is the CV itself \
+                 (limited at zero), and the exponential response rises from -60dB at 0V to unity \
+                 gain at 1V (and is silent at or below 0V, and limited to unity gain above 1V).",
+            )
+            .author("Andrew Cherry (@kolektiv)")
+            .license("AGPL-3.0-only")
+            .with_tag("utility")
+            .with_tag("vca")
+            .sleep(true)
+            .with_input(|input| input.name("Input"))
+            .with_input(|input| input.name("CV").description("Gain (0V to 1V)"))
+            .with_output(|output| output.name("Output"))
+    }
+}
+
+impl<R> ModuleInstantiate for Vca<R> {
+    type Context = R;
+
+    fn instantiate(
+        _context: Self::Context,
+        _config: Self::Config,
+        port_inputs: PortInputs,
+        port_outputs: PortOutputs,
+    ) -> Self {
+        Self::new(port_inputs, port_outputs)
+    }
+}
+
+impl<R> ModuleState for Vca<R> {
+    type State = VcaState;
+
+    fn state(&self) -> Self::State {
+        self.state
+    }
+
+    fn restore(&mut self, state: Self::State) {
+        self.state = state;
+    }
+}
+
+impl<R> Process for Vca<R> {
+    fn process(&mut self, args: &ProcessArgs) {
+        if let Some(Port::Connected(input)) = self.port_inputs.vector(0, &args.token)
+            && let Some(Port::Connected(output)) = self.port_outputs.vector(0, &args.token)
+        {
+            let level = Vector::splat(self.state.level);
+
+            *output = match self.port_inputs.vector(1, &args.token) {
+                Some(Port::Connected(cv)) => input * self.state.response.gain(cv) * level,
+                _ => input * level,
+            };
+        }
+    }
+}
+
+// Response
+
+/// The `VcaResponse` of the gain to the CV input.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VcaResponse {
+    #[default]
+    Linear,
+    Exponential,
+}
+
+impl VcaResponse {
+    fn gain(self, cv: &Vector) -> Vector {
+        let zero = Vector::splat(0.);
+
+        match self {
+            Self::Linear => cv.simd_max(zero),
+            Self::Exponential => {
+                let cv = cv.simd_clamp(zero, Vector::splat(1.));
+                let decibels = (cv - Vector::splat(1.)) * Vector::splat(EXPONENTIAL_RANGE);
+                let gain = (decibels * Vector::splat(Sample::log2(10.) / 20.)).exp2();
+
+                cv.simd_gt(zero).select(gain, zero)
+            }
+        }
+    }
+}
+
+// State
+
+/// The state of a VCA (the controls, which can be set by restoring a state).
+#[derive(new, Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct VcaState {
+    /// The level (gain at full CV)
+    level: Sample,
+    /// The response of the gain to the CV input
+    #[serde(default)]
+    response: VcaResponse,
+}
+
+impl Default for VcaState {
+    fn default() -> Self {
+        Self::new(1., VcaResponse::default())
+    }
+}